@@ -0,0 +1,229 @@
+use std::{
+    fs::{self, File, Metadata},
+    io::{self, BufWriter, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use flate2::{write::GzEncoder, Compression};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    actions::paths::PathStyle,
+    matcher::archive::ArchiveFormat,
+    types::{FindError, FindResult, FoundFile},
+};
+
+/// 将所有匹配项写入一个 tar / tar.gz / zip 压缩包，成员路径相对于搜索起点
+pub fn create_archive(matches: &[FoundFile], output: &Path, root: &Path, style: &PathStyle) -> FindResult<()> {
+    let format = ArchiveFormat::from_path(output).ok_or_else(|| {
+        FindError::Archive(format!(
+            "{}: unsupported archive format (use .tar, .tar.gz, .tgz or .zip)",
+            output.display()
+        ))
+    })?;
+
+    let entries = collect_entries(matches, output, root, style);
+    let file = BufWriter::new(File::create(output)?);
+
+    match format {
+        ArchiveFormat::Tar => write_tar(file, &entries)?.flush()?,
+        ArchiveFormat::TarGz => {
+            let encoder = write_tar(GzEncoder::new(file, Compression::default()), &entries)?;
+            encoder.finish()?.flush()?;
+        }
+        ArchiveFormat::Zip => write_zip(file, &entries)?,
+    }
+
+    eprintln!("Archived {} entries to {}", entries.len(), output.display());
+    Ok(())
+}
+
+/// 计算每个匹配项在压缩包中的名称
+///
+/// 默认使用相对于搜索起点的路径；`--absolute` 时去掉开头的 `/`，
+/// `--relative-to` 时使用相对于该目录的路径，不在该目录下的条目被跳过。
+fn collect_entries(matches: &[FoundFile], output: &Path, root: &Path, style: &PathStyle) -> Vec<(PathBuf, PathBuf)> {
+    // 输出文件位于搜索目录中时不能把自己也打包进去
+    let output = fs::canonicalize(output).ok();
+
+    matches
+        .iter()
+        .filter(|file| file.member.is_none())
+        .filter(|file| output.is_none() || fs::canonicalize(&file.path).ok() != output)
+        .filter_map(|file| {
+            let relative = match style {
+                PathStyle::Absolute | PathStyle::RelativeTo(_) => style.apply(&file.path),
+                PathStyle::AsFound | PathStyle::StripRoot(_) => {
+                    file.path.strip_prefix(root).unwrap_or(&file.path).to_path_buf()
+                }
+            };
+            if relative.components().any(|c| c == Component::ParentDir) {
+                eprintln!("Skipping {}: not inside the --relative-to directory", file.path.display());
+                return None;
+            }
+            let name: PathBuf = if relative.components().all(|c| c == Component::CurDir) {
+                // 搜索起点本身是文件时使用文件名，是目录时不单独存储
+                if file.path.is_dir() {
+                    return None;
+                }
+                PathBuf::from(file.path.file_name()?)
+            } else {
+                relative
+                    .components()
+                    .filter(|c| matches!(c, Component::Normal(_)))
+                    .collect()
+            };
+            Some((file.path.clone(), name))
+        })
+        .collect()
+}
+
+fn write_tar<W: Write>(writer: W, entries: &[(PathBuf, PathBuf)]) -> FindResult<W> {
+    let mut builder = tar::Builder::new(writer);
+    // 符号链接按链接本身存储，权限、属主和修改时间都来自原文件
+    builder.follow_symlinks(false);
+
+    for (path, name) in entries {
+        builder
+            .append_path_with_name(path, name)
+            .map_err(|e| FindError::Archive(format!("{}: {}", path.display(), e)))?;
+    }
+
+    Ok(builder.into_inner()?)
+}
+
+fn write_zip<W: Write + io::Seek>(writer: W, entries: &[(PathBuf, PathBuf)]) -> FindResult<()> {
+    let mut zip = ZipWriter::new(writer);
+    let to_archive_error = |path: &Path, e: zip::result::ZipError| {
+        FindError::Archive(format!("{}: {}", path.display(), e))
+    };
+
+    for (path, name) in entries {
+        let metadata = fs::symlink_metadata(path)?;
+        let options = zip_options(&metadata);
+        // zip 成员统一使用 `/` 作为分隔符
+        let name = name
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if metadata.is_dir() {
+            zip.add_directory(name, options).map_err(|e| to_archive_error(path, e))?;
+        } else if metadata.file_type().is_symlink() {
+            let target = fs::read_link(path)?;
+            zip.add_symlink(name, target.to_string_lossy(), options)
+                .map_err(|e| to_archive_error(path, e))?;
+        } else {
+            zip.start_file(name, options).map_err(|e| to_archive_error(path, e))?;
+            io::copy(&mut File::open(path)?, &mut zip)?;
+        }
+    }
+
+    zip.finish().map_err(|e| FindError::Archive(e.to_string()))?.flush()?;
+    Ok(())
+}
+
+fn zip_options(metadata: &Metadata) -> SimpleFileOptions {
+    let mut options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(metadata.len() >= u32::MAX as u64);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        options = options.unix_permissions(metadata.permissions().mode() & 0o7777);
+    }
+
+    // zip 中保存不带时区的本地时间，且不支持 1980 年之前的时间
+    let modified = metadata
+        .modified()
+        .ok()
+        .map(|t| DateTime::<Local>::from(t).naive_local())
+        .and_then(|t| zip::DateTime::try_from(t).ok());
+    if let Some(modified) = modified {
+        options = options.last_modified_time(modified);
+    }
+
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{matcher::archive::read_members, types::FilterType};
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    fn found(root: &Path, relative: &str) -> FoundFile {
+        FoundFile {
+            path: root.join(relative),
+            depth: Path::new(relative).components().count(),
+            member: None,
+        }
+    }
+
+    fn setup() -> (TempDir, Vec<FoundFile>) {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("etc")).unwrap();
+        let config = dir.path().join("etc/app.conf");
+        fs::write(&config, "key = value").unwrap();
+        File::options()
+            .write(true)
+            .open(&config)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+            .unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&config, fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        let matches = vec![found(dir.path(), ""), found(dir.path(), "etc"), found(dir.path(), "etc/app.conf")];
+        (dir, matches)
+    }
+
+    #[test]
+    fn test_tar_gz_preserves_metadata() {
+        let (dir, matches) = setup();
+        let output = dir.path().join("backup.tar.gz");
+        create_archive(&matches, &output, dir.path(), &PathStyle::AsFound).unwrap();
+
+        let members = read_members(&output).unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].name, PathBuf::from("etc"));
+        assert_eq!(members[0].kind, FilterType::Directory);
+        assert_eq!(members[1].name, PathBuf::from("etc/app.conf"));
+        assert_eq!(members[1].size, 11);
+        assert_eq!(members[1].modified, SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        #[cfg(unix)]
+        assert_eq!(members[1].mode, 0o600);
+    }
+
+    #[test]
+    fn test_zip_skips_output_file() {
+        let (dir, mut matches) = setup();
+        let output = dir.path().join("backup.zip");
+        fs::write(&output, "").unwrap();
+        matches.push(found(dir.path(), "backup.zip"));
+        create_archive(&matches, &output, dir.path(), &PathStyle::AsFound).unwrap();
+
+        let members = read_members(&output).unwrap();
+        let names: Vec<_> = members.iter().map(|m| m.name.clone()).collect();
+        assert_eq!(names, vec![PathBuf::from("etc"), PathBuf::from("etc/app.conf")]);
+        #[cfg(unix)]
+        assert_eq!(members[1].mode, 0o600);
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        let dir = TempDir::new().unwrap();
+        assert!(matches!(
+            create_archive(&[], &dir.path().join("out.rar"), dir.path(), &PathStyle::AsFound),
+            Err(FindError::Archive(_))
+        ));
+    }
+}
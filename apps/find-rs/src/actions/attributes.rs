@@ -0,0 +1,208 @@
+use std::{
+    fs,
+    io,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use filetime::FileTime;
+
+use crate::{
+    actions::{mode::ModeSpec, owner},
+    types::{FileActions, FindError, FindResult},
+};
+
+/// `--chown` 的目标属主，`user`、`user:group` 或 `:group`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Owner {
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+}
+
+impl Owner {
+    pub fn parse(spec: &str) -> FindResult<Self> {
+        let (user, group) = spec.split_once(':').unwrap_or((spec, ""));
+
+        let uid = match user {
+            "" => None,
+            user => Some(owner::user_id(user).ok_or_else(|| {
+                FindError::Attribute(format!("unknown user '{}'", user))
+            })?),
+        };
+        let gid = match group {
+            "" => None,
+            group => Some(owner::group_id(group).ok_or_else(|| {
+                FindError::Attribute(format!("unknown group '{}'", group))
+            })?),
+        };
+
+        if uid.is_none() && gid.is_none() {
+            return Err(FindError::Attribute(format!("invalid owner '{}'", spec)));
+        }
+        Ok(Owner { uid, gid })
+    }
+}
+
+/// 解析 `--touch` 的时间，支持 `@epoch`、`YYYY-MM-DD`、`YYYY-MM-DD HH:MM[:SS]` 和 RFC 3339
+pub fn parse_timestamp(s: &str) -> FindResult<SystemTime> {
+    let s = s.trim();
+    let invalid = || FindError::TimeSpec(format!("invalid timestamp '{}'", s));
+
+    if let Some(secs) = s.strip_prefix('@') {
+        let secs: u64 = secs.parse().map_err(|_| invalid())?;
+        return Ok(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+    }
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.into());
+    }
+
+    // 不带时区的时间按本地时间处理
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(invalid)?;
+
+    naive
+        .and_local_timezone(Local)
+        .earliest()
+        .map(SystemTime::from)
+        .ok_or_else(invalid)
+}
+
+/// 对一个文件执行 --chmod / --chown / --touch，单个文件失败时只报告错误
+pub fn update_attributes(path: &Path, actions: &FileActions) {
+    if let Some(mode) = &actions.chmod {
+        if let Err(e) = chmod(path, mode) {
+            eprintln!("Failed to change mode of {}: {}", path.display(), e);
+        }
+    }
+
+    if let Some(owner) = &actions.chown {
+        if let Err(e) = chown(path, owner) {
+            eprintln!("Failed to change owner of {}: {}", path.display(), e);
+        }
+    }
+
+    if let Some(time) = &actions.touch {
+        if let Err(e) = touch(path, time.unwrap_or_else(SystemTime::now)) {
+            eprintln!("Failed to touch {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn chmod(path: &Path, mode: &ModeSpec) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let metadata = fs::symlink_metadata(path)?;
+    // 符号链接本身没有权限，也不应修改链接指向的文件
+    if metadata.file_type().is_symlink() {
+        return Ok(());
+    }
+
+    let current = metadata.permissions().mode() & 0o7777;
+    let new = mode.apply(current, metadata.is_dir());
+    if new != current {
+        fs::set_permissions(path, fs::Permissions::from_mode(new))?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn chown(path: &Path, owner: &Owner) -> io::Result<()> {
+    // 与 chown -h 一样修改符号链接本身
+    std::os::unix::fs::lchown(path, owner.uid, owner.gid)
+}
+
+#[cfg(not(unix))]
+fn chmod(_path: &Path, _mode: &ModeSpec) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "--chmod is only supported on Unix"))
+}
+
+#[cfg(not(unix))]
+fn chown(_path: &Path, _owner: &Owner) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "--chown is only supported on Unix"))
+}
+
+/// 同时设置访问时间和修改时间
+///
+/// 不需要打开文件，因此只写或不可读的文件同样有效；与 `touch -h` 一样修改符号链接本身。
+/// FIFO、设备和套接字被跳过。
+fn touch(path: &Path, time: SystemTime) -> io::Result<()> {
+    let file_type = fs::symlink_metadata(path)?.file_type();
+    if !(file_type.is_file() || file_type.is_dir() || file_type.is_symlink()) {
+        eprintln!("Skipping touch of special file {}", path.display());
+        return Ok(());
+    }
+    let time = FileTime::from_system_time(time);
+    filetime::set_symlink_file_times(path, time, time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_owner_spec() {
+        assert_eq!(Owner::parse("0:0").unwrap(), Owner { uid: Some(0), gid: Some(0) });
+        assert_eq!(Owner::parse(":0").unwrap(), Owner { uid: None, gid: Some(0) });
+        assert_eq!(Owner::parse("1000").unwrap(), Owner { uid: Some(1000), gid: None });
+        assert!(Owner::parse(":").is_err());
+        assert!(Owner::parse("no-such-user-here").is_err());
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(
+            parse_timestamp("@1700000000").unwrap(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+        );
+        assert_eq!(
+            parse_timestamp("2023-11-14T22:13:20Z").unwrap(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+        );
+        assert!(parse_timestamp("2024-01-31").is_ok());
+        assert!(parse_timestamp("2024-01-31 08:30").is_ok());
+        assert!(parse_timestamp("yesterday").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_chmod_and_touch() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("shared.txt");
+        fs::write(&path, "").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o666)).unwrap();
+
+        chmod(&path, &ModeSpec::parse("o-w").unwrap()).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o7777, 0o664);
+
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        touch(&path, time).unwrap();
+        touch(dir.path(), time).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), time);
+        assert_eq!(fs::metadata(dir.path()).unwrap().modified().unwrap(), time);
+
+        // 不可读的文件也能修改时间；符号链接只修改链接本身
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o200)).unwrap();
+        let later = time + Duration::from_secs(60);
+        touch(&path, later).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), later);
+
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+        touch(&link, time).unwrap();
+        assert_eq!(fs::symlink_metadata(&link).unwrap().modified().unwrap(), time);
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), later);
+    }
+}
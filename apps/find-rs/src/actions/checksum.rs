@@ -0,0 +1,276 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use md5::Md5;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::types::{FindError, FindResult, FoundFile, HashAlgorithm};
+
+const BUF_SIZE: usize = 64 * 1024;
+
+/// 计算文件的十六进制摘要
+pub fn hash_file(path: &Path, algorithm: HashAlgorithm) -> io::Result<String> {
+    let file = File::open(path)?;
+    match algorithm {
+        HashAlgorithm::Sha256 => digest_reader::<Sha256>(file),
+        HashAlgorithm::Md5 => digest_reader::<Md5>(file),
+        HashAlgorithm::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+            hasher.update_reader(file)?;
+            Ok(hasher.finalize().to_hex().to_string())
+        }
+    }
+}
+
+fn digest_reader<D: Digest>(mut reader: impl Read) -> io::Result<String> {
+    let mut hasher = D::new();
+    let mut buf = vec![0u8; BUF_SIZE];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 并行计算所有普通文件的摘要，按 `sha256sum` 的格式输出
+pub fn print_checksums(matches: &[FoundFile], algorithm: HashAlgorithm) -> FindResult<()> {
+    let results: Vec<(&Path, io::Result<String>)> = matches
+        .par_iter()
+        .filter(|file| file.member.is_none() && file.path.is_file())
+        .map(|file| (file.path.as_path(), hash_file(&file.path, algorithm)))
+        .collect();
+
+    let mut stdout = io::stdout().lock();
+    for (path, result) in results {
+        match result {
+            Ok(sum) => stdout.write_all(&manifest_line(&sum, path))?,
+            Err(e) => eprintln!("Failed to hash {}: {}", path.display(), e),
+        }
+    }
+
+    Ok(())
+}
+
+/// 生成一行清单（含换行），与 `sha256sum` 一样对包含 `\` 或换行的路径进行转义；
+/// 非 UTF-8 的文件名按原始字节写出，校验时可以还原
+fn manifest_line(sum: &str, path: &Path) -> Vec<u8> {
+    let bytes = path.as_os_str().as_encoded_bytes();
+    let mut line = Vec::with_capacity(sum.len() + bytes.len() + 4);
+    if bytes.contains(&b'\\') || bytes.contains(&b'\n') {
+        line.push(b'\\');
+    }
+    line.extend_from_slice(sum.as_bytes());
+    line.extend_from_slice(b"  ");
+    for byte in bytes {
+        match byte {
+            b'\\' => line.extend_from_slice(b"\\\\"),
+            b'\n' => line.extend_from_slice(b"\\n"),
+            _ => line.push(*byte),
+        }
+    }
+    line.push(b'\n');
+    line
+}
+
+/// 解析清单中的一行（不含换行），返回 (摘要, 路径)
+fn parse_manifest_line(line: &[u8]) -> Option<(String, PathBuf)> {
+    let (escaped, line) = match line.strip_prefix(b"\\") {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+
+    let split = line.iter().position(|b| *b == b' ')?;
+    let (sum, path) = (&line[..split], &line[split + 1..]);
+    // 第二个字符为 '*' 表示二进制模式，' ' 表示文本模式，两者对摘要没有影响
+    let path = path.strip_prefix(b"*").or_else(|| path.strip_prefix(b" ")).unwrap_or(path);
+    if sum.is_empty() || !sum.iter().all(u8::is_ascii_hexdigit) || path.is_empty() {
+        return None;
+    }
+
+    let path = if escaped { unescape(path)? } else { path.to_vec() };
+    let sum = String::from_utf8_lossy(sum).to_ascii_lowercase();
+    Some((sum, path_from_bytes(path)))
+}
+
+fn unescape(path: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(path.len());
+    let mut bytes = path.iter();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'\\' => match bytes.next()? {
+                b'\\' => output.push(b'\\'),
+                b'n' => output.push(b'\n'),
+                _ => return None,
+            },
+            _ => output.push(*byte),
+        }
+    }
+    Some(output)
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+
+    PathBuf::from(OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// 根据摘要长度推断候选算法：32 位为 md5；64 位可能是 sha256 或 blake3，依次尝试
+fn candidate_algorithms(sum: &str) -> &'static [HashAlgorithm] {
+    if sum.len() == 32 {
+        &[HashAlgorithm::Md5]
+    } else {
+        &[HashAlgorithm::Sha256, HashAlgorithm::Blake3]
+    }
+}
+
+/// 用给定（或推断出的）算法检查文件摘要
+fn check_file(path: &Path, expected: &str, algorithm: Option<HashAlgorithm>) -> VerifyStatus {
+    let candidates = match &algorithm {
+        Some(algorithm) => std::slice::from_ref(algorithm),
+        None => candidate_algorithms(expected),
+    };
+    for algorithm in candidates {
+        match hash_file(path, *algorithm) {
+            Ok(actual) if actual == expected => return VerifyStatus::Ok,
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return VerifyStatus::Missing,
+            Err(e) => return VerifyStatus::Unreadable(e.to_string()),
+        }
+    }
+    VerifyStatus::Mismatch
+}
+
+#[derive(Debug, PartialEq)]
+enum VerifyStatus {
+    Ok,
+    Mismatch,
+    Missing,
+    Unreadable(String),
+}
+
+/// 校验清单中的每个文件，输出 `path: OK/FAILED/MISSING`
+pub fn verify_manifest(manifest: &Path, algorithm: Option<HashAlgorithm>) -> FindResult<()> {
+    let content = fs::read(manifest)?;
+    let entries: Vec<(String, PathBuf)> = content
+        .split(|b| *b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|line| !line.trim_ascii().is_empty())
+        .map(|line| {
+            parse_manifest_line(line).ok_or_else(|| {
+                FindError::Checksum(format!(
+                    "{}: malformed line '{}'",
+                    manifest.display(),
+                    String::from_utf8_lossy(line)
+                ))
+            })
+        })
+        .collect::<FindResult<_>>()?;
+
+    let results: Vec<VerifyStatus> = entries
+        .par_iter()
+        .map(|(expected, path)| check_file(path, expected, algorithm))
+        .collect();
+
+    let mut failed = 0;
+    for ((_, path), status) in entries.iter().zip(&results) {
+        match status {
+            VerifyStatus::Ok => println!("{}: OK", path.display()),
+            VerifyStatus::Mismatch => println!("{}: FAILED", path.display()),
+            VerifyStatus::Missing => println!("{}: MISSING", path.display()),
+            VerifyStatus::Unreadable(e) => println!("{}: FAILED open or read ({})", path.display(), e),
+        }
+        if *status != VerifyStatus::Ok {
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        return Err(FindError::Checksum(format!(
+            "{} of {} file(s) did not match the manifest",
+            failed,
+            entries.len()
+        )));
+    }
+
+    eprintln!("All {} file(s) match the manifest", entries.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_known_digests() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("abc.txt");
+        fs::write(&path, "abc").unwrap();
+
+        assert_eq!(
+            hash_file(&path, HashAlgorithm::Sha256).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(hash_file(&path, HashAlgorithm::Md5).unwrap(), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(
+            hash_file(&path, HashAlgorithm::Blake3).unwrap(),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+    }
+
+    #[test]
+    fn test_manifest_lines_round_trip() {
+        let sum = "900150983cd24fb0d6963f7d28e17f72";
+        for name in ["plain.txt", "back\\slash", "new\nline"] {
+            let line = manifest_line(sum, Path::new(name));
+            let line = line.strip_suffix(b"\n").unwrap();
+            assert_eq!(parse_manifest_line(line), Some((sum.to_string(), PathBuf::from(name))));
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+
+            let name = Path::new(std::ffi::OsStr::from_bytes(b"caf\xe9\\x"));
+            let line = manifest_line(sum, name);
+            let line = line.strip_suffix(b"\n").unwrap();
+            assert_eq!(parse_manifest_line(line), Some((sum.to_string(), name.to_path_buf())));
+        }
+
+        assert_eq!(
+            parse_manifest_line(format!("{} *bin.dat", sum).as_bytes()),
+            Some((sum.to_string(), PathBuf::from("bin.dat")))
+        );
+        assert_eq!(parse_manifest_line(b"not a checksum line"), None);
+    }
+
+    #[test]
+    fn test_verify_blake3_without_algorithm() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("abc.txt");
+        fs::write(&path, "abc").unwrap();
+
+        // blake3 与 sha256 的摘要长度相同，未指定算法时两者都会尝试
+        let sum = hash_file(&path, HashAlgorithm::Blake3).unwrap();
+        assert_eq!(check_file(&path, &sum, None), VerifyStatus::Ok);
+        assert_eq!(check_file(&path, &sum, Some(HashAlgorithm::Sha256)), VerifyStatus::Mismatch);
+    }
+}
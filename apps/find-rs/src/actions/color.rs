@@ -0,0 +1,357 @@
+use std::{
+    collections::HashMap,
+    env,
+    fs::{self, Metadata},
+    io::{self, IsTerminal},
+    ops::Range,
+    path::Path,
+};
+
+use regex::RegexBuilder;
+
+use crate::{
+    actions::paths::PathStyle,
+    types::{Cli, ColorChoice, FilterType, FoundFile},
+};
+
+/// 没有设置 `LS_COLORS` 时使用的配色，与 GNU dircolors 的默认值接近
+const DEFAULT_LS_COLORS: &str = "di=01;34:ln=01;36:or=01;31:mi=01;31:ex=01;32:pi=33:so=01;35:\
+bd=01;33:cd=01;33:su=37;41:sg=30;43:tw=30;42:ow=34;42:\
+*.tar=01;31:*.tgz=01;31:*.gz=01;31:*.zip=01;31:*.xz=01;31:*.bz2=01;31:*.zst=01;31:*.7z=01;31:\
+*.jpg=01;35:*.jpeg=01;35:*.png=01;35:*.gif=01;35:*.svg=01;35:*.webp=01;35:\
+*.mp3=00;36:*.flac=00;36:*.wav=00;36:*.mp4=01;35:*.mkv=01;35";
+
+/// 文件名中被 `--name`/`--regex` 匹配到的部分额外加粗并加下划线
+const HIGHLIGHT: &str = "1;4";
+
+/// 解析后的 `LS_COLORS`
+#[derive(Debug, Default)]
+pub struct LsColors {
+    /// 按类型的颜色，键为 di、ln、ex 等两字母代码
+    types: HashMap<String, String>,
+    /// 按扩展名的颜色，键为小写的扩展名（不含点）
+    extensions: HashMap<String, String>,
+}
+
+impl LsColors {
+    /// 解析 `di=01;34:*.tar=01;31` 格式，无法识别的条目直接忽略
+    pub fn parse(spec: &str) -> LsColors {
+        let mut colors = LsColors::default();
+        for entry in spec.split(':') {
+            let Some((key, value)) = entry.split_once('=') else {
+                continue;
+            };
+            if value.is_empty() {
+                continue;
+            }
+            match key.strip_prefix("*.") {
+                Some(ext) => colors.extensions.insert(ext.to_lowercase(), value.to_string()),
+                None => colors.types.insert(key.to_string(), value.to_string()),
+            };
+        }
+        colors
+    }
+
+    pub fn from_env() -> LsColors {
+        match env::var("LS_COLORS") {
+            Ok(spec) if !spec.is_empty() => LsColors::parse(&spec),
+            _ => LsColors::parse(DEFAULT_LS_COLORS),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.types.get(key).map(String::as_str)
+    }
+
+    /// 条目的颜色：特殊类型优先，普通文件再按可执行和扩展名区分
+    fn style_for(&self, path: &Path, metadata: Option<&Metadata>) -> Option<&str> {
+        let Some(metadata) = metadata else {
+            return self.get("mi");
+        };
+        let file_type = metadata.file_type();
+
+        if file_type.is_symlink() {
+            return match fs::metadata(path) {
+                Ok(_) => self.get("ln"),
+                Err(_) => self.get("or").or_else(|| self.get("ln")),
+            };
+        }
+        if file_type.is_dir() {
+            return self.dir_style(metadata);
+        }
+        if let Some(style) = self.special_style(metadata) {
+            return Some(style);
+        }
+        if is_executable(metadata) {
+            if let Some(style) = self.get("ex") {
+                return Some(style);
+            }
+        }
+        self.extension_style(path).or_else(|| self.get("fi"))
+    }
+
+    fn extension_style(&self, path: &Path) -> Option<&str> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        // 允许 `*.tar.gz` 这样的多段扩展名，优先匹配最长的
+        name.match_indices('.')
+            .find_map(|(pos, _)| self.extensions.get(&name[pos + 1..]))
+            .map(String::as_str)
+    }
+
+    #[cfg(unix)]
+    fn dir_style(&self, metadata: &Metadata) -> Option<&str> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = metadata.permissions().mode();
+        let (sticky, other_writable) = (mode & 0o1000 != 0, mode & 0o002 != 0);
+        let key = match (sticky, other_writable) {
+            (true, true) => "tw",
+            (false, true) => "ow",
+            (true, false) => "st",
+            (false, false) => "di",
+        };
+        self.get(key).or_else(|| self.get("di"))
+    }
+
+    #[cfg(not(unix))]
+    fn dir_style(&self, _metadata: &Metadata) -> Option<&str> {
+        self.get("di")
+    }
+
+    #[cfg(unix)]
+    fn special_style(&self, metadata: &Metadata) -> Option<&str> {
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+        let file_type = metadata.file_type();
+        let mode = metadata.permissions().mode();
+        let key = if file_type.is_fifo() {
+            "pi"
+        } else if file_type.is_socket() {
+            "so"
+        } else if file_type.is_block_device() {
+            "bd"
+        } else if file_type.is_char_device() {
+            "cd"
+        } else if mode & 0o4000 != 0 {
+            "su"
+        } else if mode & 0o2000 != 0 {
+            "sg"
+        } else {
+            return None;
+        };
+        self.get(key)
+    }
+
+    #[cfg(not(unix))]
+    fn special_style(&self, _metadata: &Metadata) -> Option<&str> {
+        None
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &Metadata) -> bool {
+    false
+}
+
+/// 找出文件名中被搜索条件匹配到的区间（字节偏移）
+#[derive(Debug)]
+enum Highlighter {
+    Regex(regex::Regex),
+    /// 通配符模式中按顺序出现的字面量片段
+    Glob { literals: Vec<String>, case_insensitive: bool },
+}
+
+impl Highlighter {
+    fn from_cli(cli: &Cli) -> Option<Highlighter> {
+        let criteria = &cli.criteria;
+        if let Some(pattern) = &criteria.regex {
+            let regex = RegexBuilder::new(pattern)
+                .case_insensitive(criteria.ignore_case(pattern, true))
+                .build()
+                .ok()?;
+            return Some(Highlighter::Regex(regex));
+        }
+
+        let pattern = criteria.name.as_deref()?;
+        let case_insensitive = criteria.ignore_case(pattern, false);
+        let literals = pattern
+            .split(['*', '?'])
+            .filter(|literal| !literal.is_empty())
+            .map(|literal| if case_insensitive { literal.to_lowercase() } else { literal.to_string() })
+            .collect();
+        Some(Highlighter::Glob { literals, case_insensitive })
+    }
+
+    fn ranges(&self, name: &str) -> Vec<Range<usize>> {
+        match self {
+            Highlighter::Regex(regex) => regex
+                .find_iter(name)
+                .map(|m| m.range())
+                .filter(|range| !range.is_empty())
+                .collect(),
+            Highlighter::Glob { literals, case_insensitive } => {
+                // 只有小写后字节长度不变时才能用小写文本中的偏移定位原文
+                let lowered = name.to_lowercase();
+                let haystack = if *case_insensitive && lowered.len() == name.len() { lowered.as_str() } else { name };
+
+                let mut ranges = Vec::new();
+                let mut from = 0;
+                for literal in literals {
+                    let Some(pos) = haystack[from..].find(literal.as_str()) else {
+                        return Vec::new();
+                    };
+                    ranges.push(from + pos..from + pos + literal.len());
+                    from += pos + literal.len();
+                }
+                ranges
+            }
+        }
+    }
+}
+
+/// 按文件类型为路径着色
+#[derive(Debug)]
+pub struct Painter {
+    colors: LsColors,
+    highlighter: Option<Highlighter>,
+}
+
+impl Painter {
+    /// 根据 `--color` 决定是否着色；`auto` 时只在 stdout 是终端且未设置 `NO_COLOR` 时启用
+    pub fn from_cli(cli: &Cli) -> Option<Painter> {
+        let enabled = match cli.actions.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none(),
+        };
+        enabled.then(|| Painter::new(LsColors::from_env(), Highlighter::from_cli(cli)))
+    }
+
+    fn new(colors: LsColors, highlighter: Option<Highlighter>) -> Painter {
+        Painter { colors, highlighter }
+    }
+
+    /// 上级目录部分使用目录颜色，文件名使用自身类型的颜色并标出匹配部分
+    pub fn paint(&self, file: &FoundFile, style: &PathStyle) -> String {
+        self.paint_text(file, &style.display(file).to_string_lossy())
+    }
+
+    /// 按 `file` 的类型为它的某种显示形式着色，例如 `--print` 中的 `%P` 和 `%f`
+    pub fn paint_text(&self, file: &FoundFile, text: &str) -> String {
+        let name_start = match Path::new(text).file_name() {
+            Some(name) => text.len() - name.to_string_lossy().len(),
+            None => text.len(),
+        };
+        let (parent, name) = text.split_at(name_start);
+
+        let name_style = match &file.member {
+            Some(member) => match member.kind {
+                FilterType::Directory => self.colors.get("di"),
+                FilterType::Symlink => self.colors.get("ln"),
+                _ => self.colors.extension_style(&file.path).or_else(|| self.colors.get("fi")),
+            },
+            None => {
+                let metadata = fs::symlink_metadata(&file.path).ok();
+                self.colors.style_for(&file.path, metadata.as_ref())
+            }
+        };
+
+        let mut output = String::new();
+        push_styled(&mut output, parent, self.colors.get("di"));
+        let ranges = self.highlighter.as_ref().map(|h| h.ranges(name)).unwrap_or_default();
+        let mut last = 0;
+        for range in ranges {
+            push_styled(&mut output, &name[last..range.start], name_style);
+            let highlight = match name_style {
+                Some(style) => format!("{};{}", style, HIGHLIGHT),
+                None => HIGHLIGHT.to_string(),
+            };
+            push_styled(&mut output, &name[range.clone()], Some(&highlight));
+            last = range.end;
+        }
+        push_styled(&mut output, &name[last..], name_style);
+        output
+    }
+}
+
+fn push_styled(output: &mut String, text: &str, style: Option<&str>) {
+    match style {
+        Some(style) if !text.is_empty() => {
+            output.push_str("\x1b[");
+            output.push_str(style);
+            output.push('m');
+            output.push_str(text);
+            output.push_str("\x1b[0m");
+        }
+        _ => output.push_str(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::format::PrintFormat;
+    use clap::Parser;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn painter_for(args: &[&str], spec: &str) -> Painter {
+        let cli = Cli::try_parse_from([&["find-rs", "."], args].concat()).unwrap();
+        Painter::new(LsColors::parse(spec), Highlighter::from_cli(&cli))
+    }
+
+    #[test]
+    fn test_paint_by_type_and_extension() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub/backup.TAR.gz"), "").unwrap();
+        let painter = painter_for(&[], "di=34:*.tar.gz=31:*.gz=32");
+
+        let root = dir.path().display().to_string();
+        let file = FoundFile { path: dir.path().join("sub/backup.TAR.gz"), depth: 2, member: None };
+        assert_eq!(
+            painter.paint(&file, &PathStyle::AsFound),
+            format!("\x1b[34m{}/sub/\x1b[0m\x1b[31mbackup.TAR.gz\x1b[0m", root)
+        );
+
+        let missing = FoundFile { path: PathBuf::from("gone"), depth: 1, member: None };
+        assert_eq!(painter.paint(&missing, &PathStyle::AsFound), "gone");
+    }
+
+    #[test]
+    fn test_highlight_matches() {
+        let painter = painter_for(&["--name", "*LOG*"], "");
+        let file = FoundFile { path: PathBuf::from("var/app.log.1"), depth: 2, member: None };
+        assert_eq!(painter.paint(&file, &PathStyle::AsFound), "var/app.log.1");
+
+        let painter = painter_for(&["--name", "*log*"], "");
+        assert_eq!(painter.paint(&FoundFile { path: PathBuf::from("App.LOG"), depth: 1, member: None }, &PathStyle::AsFound), "App.\x1b[1;4mLOG\x1b[0m");
+
+        let painter = painter_for(&["--regex", r"\d+"], "");
+        assert_eq!(painter.paint(&FoundFile { path: PathBuf::from("a1b22"), depth: 1, member: None }, &PathStyle::AsFound), "a\x1b[1;4m1\x1b[0mb\x1b[1;4m22\x1b[0m");
+    }
+
+    #[test]
+    fn test_print_format_paths_are_colored() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        let painter = painter_for(&[], "di=34:*.rs=31");
+        let file = FoundFile { path: dir.path().join("src/main.rs"), depth: 2, member: None };
+
+        // 宽度按未着色的文本计算，非路径指令保持原样
+        let format = PrintFormat::parse("%-8f|%P %d").unwrap();
+        assert_eq!(
+            format.render_painted(&file, dir.path(), &PathStyle::AsFound, Some(&painter)).unwrap(),
+            "\x1b[31mmain.rs\x1b[0m |\x1b[34msrc/\x1b[0m\x1b[31mmain.rs\x1b[0m 2"
+        );
+        assert_eq!(format.render(&file, dir.path(), &PathStyle::AsFound).unwrap(), "main.rs |src/main.rs 2");
+    }
+}
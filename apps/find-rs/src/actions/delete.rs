@@ -2,17 +2,18 @@ use std::{fs, io, path::Path};
 
 use crate::types::{FindError, FindResult};
 
+/// 在 stdout 上询问，只有回答 y 时返回 true
+pub fn confirm(prompt: &str) -> bool {
+    print!("{} (y/N): ", prompt);
+    let _ = io::Write::flush(&mut io::stdout());
+
+    let mut input = String::new();
+    !(io::stdin().read_line(&mut input).is_ok() && input.trim().to_lowercase() != "y")
+}
+
 pub fn delete_file(path: &Path, force: bool) -> FindResult<()>{
-    if !force {
-        print!("Delete {}? (y/N): ", path.display());
-        let _ = io::Write::flush(&mut io::stdout());
-        
-        let mut input = String::new();
-        if io::stdin().read_line(&mut input).is_ok() {
-            if input.trim().to_lowercase() != "y" {
-                return Err(FindError::NoAction);
-            }
-        }
+    if !force && !confirm(&format!("Delete {}?", path.display())) {
+        return Err(FindError::NoAction);
     }
     
     if path.is_dir() {
@@ -24,4 +25,42 @@ pub fn delete_file(path: &Path, force: bool) -> FindResult<()>{
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+/// 删除通过 `--empty` 匹配到的目录
+///
+/// 搜索时把待删除的子项视为已删除，但子项可能在确认时被拒绝或删除失败，
+/// 因此在删除前重新检查目录是否真的为空，并且只用 `remove_dir`，不会递归删除。
+pub fn delete_empty_dir(path: &Path, force: bool) -> FindResult<()> {
+    if fs::read_dir(path).map_or(true, |mut entries| entries.next().is_some()) {
+        eprintln!("Skipping {}: directory is not empty", path.display());
+        return Ok(());
+    }
+    if !force && !confirm(&format!("Delete {}?", path.display())) {
+        return Err(FindError::NoAction);
+    }
+    if let Err(e) = fs::remove_dir(path) {
+        eprintln!("Failed to delete directory {}: {}", path.display(), e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_delete_empty_dir_keeps_remaining_children() {
+        let dir = TempDir::new().unwrap();
+        let parent = dir.path().join("a");
+        fs::create_dir_all(parent.join("kept")).unwrap();
+
+        // 子目录被保留时，父目录不会被删除
+        delete_empty_dir(&parent, true).unwrap();
+        assert!(parent.join("kept").exists());
+
+        delete_empty_dir(&parent.join("kept"), true).unwrap();
+        delete_empty_dir(&parent, true).unwrap();
+        assert!(!parent.exists());
+    }
+}
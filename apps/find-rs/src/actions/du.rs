@@ -0,0 +1,213 @@
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    actions::print::format_human_size,
+    types::{Cli, FindResult, FoundFile},
+};
+
+/// 磁盘占用树中的一个条目，目录的大小和文件数是其下所有匹配文件之和
+#[derive(Debug)]
+pub struct DuNode {
+    pub name: OsString,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    /// 表观大小（字节），硬链接会被重复计算
+    pub size: u64,
+    pub files: u64,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+/// 按目录汇总的匹配文件大小，`nodes[0]` 为搜索起点
+#[derive(Debug)]
+pub struct DuTree {
+    pub nodes: Vec<DuNode>,
+}
+
+impl DuTree {
+    /// 由匹配结果建立目录树；只统计匹配到的文件，未匹配的文件不计入目录大小
+    pub fn build(matches: &[FoundFile], root: &Path) -> DuTree {
+        let mut tree = DuTree {
+            nodes: vec![DuNode {
+                name: root.as_os_str().to_os_string(),
+                path: root.to_path_buf(),
+                is_dir: true,
+                size: 0,
+                files: 0,
+                parent: None,
+                children: Vec::new(),
+            }],
+        };
+        let mut dirs: HashMap<PathBuf, usize> = HashMap::new();
+
+        // 压缩包成员没有实际占用，起点之外的路径无法挂到树上
+        for file in matches.iter().filter(|file| file.member.is_none()) {
+            let Ok(relative) = file.path.strip_prefix(root) else {
+                continue;
+            };
+            let Some(name) = relative.file_name() else {
+                continue;
+            };
+            let metadata = match fs::symlink_metadata(&file.path) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    eprintln!("Warning: Cannot stat {}: {}", file.path.display(), e);
+                    continue;
+                }
+            };
+
+            let parent = match relative.parent() {
+                Some(parent) => tree.dir_node(parent, root, &mut dirs),
+                None => 0,
+            };
+            if metadata.is_dir() {
+                tree.dir_node(relative, root, &mut dirs);
+                continue;
+            }
+
+            let index = tree.push(parent, name.to_os_string(), file.path.clone(), false);
+            tree.nodes[index].size = metadata.len();
+            tree.nodes[index].files = 1;
+            tree.add_to_ancestors(parent, metadata.len());
+        }
+        tree
+    }
+
+    /// 找到或创建相对路径对应的目录节点，缺失的上级目录一并创建
+    fn dir_node(&mut self, relative: &Path, root: &Path, dirs: &mut HashMap<PathBuf, usize>) -> usize {
+        if relative.as_os_str().is_empty() {
+            return 0;
+        }
+        if let Some(index) = dirs.get(relative) {
+            return *index;
+        }
+
+        let parent = relative.parent().map_or(0, |parent| self.dir_node(parent, root, dirs));
+        let name = relative.file_name().unwrap_or(relative.as_os_str()).to_os_string();
+        let index = self.push(parent, name, root.join(relative), true);
+        dirs.insert(relative.to_path_buf(), index);
+        index
+    }
+
+    fn push(&mut self, parent: usize, name: OsString, path: PathBuf, is_dir: bool) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(DuNode {
+            name,
+            path,
+            is_dir,
+            size: 0,
+            files: 0,
+            parent: Some(parent),
+            children: Vec::new(),
+        });
+        self.nodes[parent].children.push(index);
+        index
+    }
+
+    /// 把一个文件的大小计入 `index` 及其所有上级目录
+    fn add_to_ancestors(&mut self, mut index: usize, size: u64) {
+        loop {
+            let node = &mut self.nodes[index];
+            node.size += size;
+            node.files += 1;
+            match node.parent {
+                Some(parent) => index = parent,
+                None => break,
+            }
+        }
+    }
+
+    /// `index` 及其下所有节点，上级排在下级之前
+    pub fn subtree(&self, index: usize) -> Vec<usize> {
+        let mut nodes = vec![index];
+        let mut next = 0;
+        while next < nodes.len() {
+            nodes.extend(&self.nodes[nodes[next]].children);
+            next += 1;
+        }
+        nodes
+    }
+
+    /// `ancestor` 是否为 `index` 本身或其上级
+    pub fn is_within(&self, mut index: usize, ancestor: usize) -> bool {
+        loop {
+            if index == ancestor {
+                return true;
+            }
+            match self.nodes[index].parent {
+                Some(parent) => index = parent,
+                None => return false,
+            }
+        }
+    }
+}
+
+/// `--du`：像 `du` 一样按后序列出每个目录的汇总大小
+pub fn print_usage(matches: &[FoundFile], cli: &Cli) -> FindResult<()> {
+    let tree = DuTree::build(matches, &cli.path);
+    print_dir(&tree, 0);
+    Ok(())
+}
+
+fn print_dir(tree: &DuTree, index: usize) {
+    let node = &tree.nodes[index];
+    let mut children: Vec<usize> = node.children.iter().copied().filter(|c| tree.nodes[*c].is_dir).collect();
+    children.sort_by(|a, b| tree.nodes[*a].name.cmp(&tree.nodes[*b].name));
+    for child in children {
+        print_dir(tree, child);
+    }
+    println!(
+        "{:>10}\t{}\t{}",
+        format_human_size(node.size),
+        node.files,
+        node.path.display()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn found(path: PathBuf) -> FoundFile {
+        FoundFile { path, depth: 0, member: None }
+    }
+
+    #[test]
+    fn test_sizes_aggregate_to_ancestors() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::create_dir(root.join("empty")).unwrap();
+        fs::write(root.join("a/b/x"), vec![0; 300]).unwrap();
+        fs::write(root.join("a/y"), vec![0; 200]).unwrap();
+        fs::write(root.join("z"), vec![0; 100]).unwrap();
+
+        // 目录本身可以不在匹配结果中，会根据文件路径自动创建
+        let matches: Vec<FoundFile> = ["a/b/x", "a/y", "z", "empty"]
+            .iter()
+            .map(|p| found(root.join(p)))
+            .collect();
+        let tree = DuTree::build(&matches, root);
+
+        let node = |tree: &DuTree, name: &str| {
+            tree.nodes.iter().position(|n| n.name == name && n.parent.is_some()).unwrap()
+        };
+        assert_eq!((tree.nodes[0].size, tree.nodes[0].files), (600, 3));
+        let a = node(&tree, "a");
+        assert_eq!((tree.nodes[a].size, tree.nodes[a].files), (500, 2));
+        assert!(tree.nodes[node(&tree, "empty")].is_dir);
+
+        assert!(tree.is_within(node(&tree, "x"), a));
+        assert!(!tree.is_within(node(&tree, "z"), a));
+
+        // 子树只包含匹配到的文件，上级目录排在前面
+        let subtree: Vec<&OsString> = tree.subtree(a).iter().map(|i| &tree.nodes[*i].name).collect();
+        assert_eq!(subtree, ["a", "b", "y", "x"]);
+    }
+}
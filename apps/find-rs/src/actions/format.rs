@@ -0,0 +1,640 @@
+use std::{
+    fs::{self, Metadata},
+    path::Path,
+    time::SystemTime,
+};
+
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, Local,
+};
+use clap::ValueEnum;
+
+use crate::{
+    actions::{checksum, color::Painter, owner, paths::PathStyle},
+    types::{ArchiveMember, FilterType, FindError, FindResult, FoundFile, HashAlgorithm},
+};
+
+/// 单个格式指令
+#[derive(Clone, Debug, PartialEq)]
+enum Directive {
+    Path,
+    RelativePath,
+    FileName,
+    Parent,
+    Depth,
+    Size,
+    HumanSize,
+    DateTime,
+    Time,
+    Date,
+    CustomTime(String),
+    Hash(HashAlgorithm),
+    Mode,
+    Permissions,
+    User,
+    Group,
+    Uid,
+    Gid,
+    Inode,
+    LinkTarget,
+    Blocks,
+    FileType,
+}
+
+impl Directive {
+    fn from_char(c: char) -> Option<Self> {
+        let directive = match c {
+            'p' => Directive::Path,
+            'P' => Directive::RelativePath,
+            'f' | 'n' => Directive::FileName,
+            'e' => Directive::Parent,
+            'd' => Directive::Depth,
+            's' => Directive::Size,
+            'h' => Directive::HumanSize,
+            't' => Directive::DateTime,
+            'T' => Directive::Time,
+            'D' => Directive::Date,
+            'm' => Directive::Mode,
+            'M' => Directive::Permissions,
+            'u' => Directive::User,
+            'g' => Directive::Group,
+            'U' => Directive::Uid,
+            'G' => Directive::Gid,
+            'i' => Directive::Inode,
+            'l' => Directive::LinkTarget,
+            'k' => Directive::Blocks,
+            'y' => Directive::FileType,
+            _ => return None,
+        };
+        Some(directive)
+    }
+
+    /// `--color` 启用时着色的指令
+    fn is_colored(&self) -> bool {
+        matches!(self, Directive::Path | Directive::RelativePath | Directive::FileName)
+    }
+
+    /// 路径类指令不需要读取元数据
+    fn needs_metadata(&self) -> bool {
+        !matches!(
+            self,
+            Directive::Path
+                | Directive::RelativePath
+                | Directive::FileName
+                | Directive::Parent
+                | Directive::Depth
+                | Directive::LinkTarget
+                | Directive::FileType
+                | Directive::Hash(_)
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field {
+        directive: Directive,
+        width: Option<usize>,
+        left_align: bool,
+    },
+}
+
+/// 预先解析好的 printf 风格输出格式
+///
+/// 支持 `%%` 转义、`\n`/`\t`/`\\` 转义序列、宽度与对齐（如 `%-10s`），
+/// 以及 `%T{...}` 形式的自定义 strftime 时间格式。
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrintFormat {
+    segments: Vec<Segment>,
+}
+
+impl PrintFormat {
+    pub fn parse(format: &str) -> FindResult<Self> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = format.char_indices().peekable();
+
+        while let Some((pos, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => literal.push('\n'),
+                    Some((_, 't')) => literal.push('\t'),
+                    Some((_, '\\')) => literal.push('\\'),
+                    Some((_, other)) => {
+                        literal.push('\\');
+                        literal.push(other);
+                    }
+                    None => literal.push('\\'),
+                },
+                '%' => {
+                    if chars.next_if(|&(_, c)| c == '%').is_some() {
+                        literal.push('%');
+                        continue;
+                    }
+
+                    let left_align = chars.next_if(|&(_, c)| c == '-').is_some();
+                    let mut width_str = String::new();
+                    while let Some((_, d)) = chars.next_if(|&(_, c)| c.is_ascii_digit()) {
+                        width_str.push(d);
+                    }
+                    let width = if width_str.is_empty() {
+                        None
+                    } else {
+                        Some(width_str.parse::<usize>().map_err(|_| {
+                            FindError::FormatError(format!("invalid width '{}' at position {}", width_str, pos))
+                        })?)
+                    };
+
+                    let Some((_, spec)) = chars.next() else {
+                        return Err(FindError::FormatError(format!(
+                            "incomplete directive at position {}",
+                            pos
+                        )));
+                    };
+
+                    // %T{...} 与 %H{...} 带有花括号参数
+                    let argument = if matches!(spec, 'T' | 'H') && chars.next_if(|&(_, c)| c == '{').is_some() {
+                        let mut argument = String::new();
+                        loop {
+                            match chars.next() {
+                                Some((_, '}')) => break,
+                                Some((_, c)) => argument.push(c),
+                                None => {
+                                    return Err(FindError::FormatError(format!(
+                                        "unterminated '%{}{{' at position {}",
+                                        spec, pos
+                                    )))
+                                }
+                            }
+                        }
+                        Some(argument)
+                    } else {
+                        None
+                    };
+
+                    let directive = match (spec, argument) {
+                        ('T', Some(time_format)) => {
+                            validate_time_format(&time_format)?;
+                            Directive::CustomTime(time_format)
+                        }
+                        ('H', Some(algorithm)) => {
+                            let algorithm = HashAlgorithm::from_str(&algorithm, true).map_err(|_| {
+                                FindError::FormatError(format!("unknown hash algorithm '{}' at position {}", algorithm, pos))
+                            })?;
+                            Directive::Hash(algorithm)
+                        }
+                        ('H', None) => {
+                            return Err(FindError::FormatError(format!(
+                                "expected '%H{{sha256|blake3|md5}}' at position {}",
+                                pos
+                            )))
+                        }
+                        (spec, _) => Directive::from_char(spec).ok_or_else(|| {
+                            FindError::FormatError(format!("unknown directive '%{}' at position {}", spec, pos))
+                        })?,
+                    };
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field { directive, width, left_align });
+                }
+                _ => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(PrintFormat { segments })
+    }
+
+    /// 是否包含需要读取整个文件的摘要指令
+    pub fn has_hash(&self) -> bool {
+        self.segments.iter().any(|s| matches!(s, Segment::Field { directive: Directive::Hash(_), .. }))
+    }
+
+    /// 按格式渲染一个匹配条目，`root` 为搜索起点（用于 `%P`），`%p` 按 `style` 输出
+    pub fn render(&self, file: &FoundFile, root: &Path, style: &PathStyle) -> FindResult<String> {
+        self.render_painted(file, root, style, None)
+    }
+
+    /// 同 `render`，给出 `painter` 时 `%p`、`%P` 和 `%f` 按文件类型着色，宽度按未着色的文本计算
+    pub fn render_painted(
+        &self,
+        file: &FoundFile,
+        root: &Path,
+        style: &PathStyle,
+        painter: Option<&Painter>,
+    ) -> FindResult<String> {
+        let needs_stat = self.segments.iter().any(|s| match s {
+            Segment::Field { directive, .. } => directive.needs_metadata(),
+            Segment::Literal(_) => false,
+        });
+
+        let stat = match &file.member {
+            Some(member) => Some(Stat::from_member(member)),
+            // 与之前保持一致：跟随符号链接，失效链接退回到链接本身
+            None if needs_stat => Some(Stat::from_metadata(
+                &fs::metadata(&file.path).or_else(|_| fs::symlink_metadata(&file.path))?,
+            )),
+            None => None,
+        };
+
+        let mut output = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => output.push_str(text),
+                Segment::Field { directive, width, left_align } => {
+                    let value = render_directive(directive, file, root, style, stat.as_ref());
+                    let padding = " ".repeat(width.map_or(0, |w| w.saturating_sub(value.chars().count())));
+                    let value = match painter {
+                        Some(painter) if directive.is_colored() => painter.paint_text(file, &value),
+                        _ => value,
+                    };
+                    if *left_align {
+                        output.push_str(&value);
+                        output.push_str(&padding);
+                    } else {
+                        output.push_str(&padding);
+                        output.push_str(&value);
+                    }
+                }
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+/// 渲染所需的元数据，可以来自文件系统，也可以来自压缩包成员
+pub(crate) struct Stat {
+    pub(crate) size: u64,
+    pub(crate) modified: SystemTime,
+    pub(crate) mode: u32,
+    pub(crate) type_char: char,
+    pub(crate) uid: u32,
+    pub(crate) gid: u32,
+    inode: u64,
+    kilo_blocks: u64,
+}
+
+impl Stat {
+    pub(crate) fn from_metadata(metadata: &Metadata) -> Self {
+        let (uid, gid) = unix_ids(metadata);
+        Stat {
+            size: metadata.len(),
+            modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            mode: mode_bits(metadata),
+            type_char: type_char(metadata),
+            uid,
+            gid,
+            inode: inode(metadata),
+            kilo_blocks: kilo_blocks(metadata),
+        }
+    }
+
+    pub(crate) fn from_member(member: &ArchiveMember) -> Self {
+        Stat {
+            size: member.size,
+            modified: member.modified,
+            mode: member.mode,
+            type_char: member_type_char(member),
+            uid: member.uid,
+            gid: member.gid,
+            inode: 0,
+            kilo_blocks: member.size.div_ceil(1024),
+        }
+    }
+}
+
+pub fn validate_time_format(time_format: &str) -> FindResult<()> {
+    if StrftimeItems::new(time_format).any(|item| matches!(item, Item::Error)) {
+        return Err(FindError::FormatError(format!(
+            "invalid time format '{}'",
+            time_format
+        )));
+    }
+    Ok(())
+}
+
+fn render_directive(
+    directive: &Directive,
+    file: &FoundFile,
+    root: &Path,
+    style: &PathStyle,
+    stat: Option<&Stat>,
+) -> String {
+    let path = file.path.as_path();
+
+    match directive {
+        Directive::Path => style.display(file).display().to_string(),
+        Directive::RelativePath => path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .display()
+            .to_string(),
+        Directive::FileName => path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        Directive::Parent => path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .display()
+            .to_string(),
+        Directive::Depth => file.depth.to_string(),
+        Directive::LinkTarget => match &file.member {
+            Some(member) => member
+                .link_target
+                .as_ref()
+                .map(|target| target.display().to_string())
+                .unwrap_or_default(),
+            None => fs::read_link(path)
+                .map(|target| target.display().to_string())
+                .unwrap_or_default(),
+        },
+        Directive::Hash(algorithm) => match &file.member {
+            // 压缩包成员没有对应的真实文件
+            Some(_) => String::new(),
+            // 目录等非普通文件没有摘要，读取失败时给出警告；两者都输出 "-"，保持列对齐
+            None if !path.is_file() => "-".to_string(),
+            None => checksum::hash_file(path, *algorithm).unwrap_or_else(|e| {
+                eprintln!("Warning: Failed to hash {}: {}", path.display(), e);
+                "-".to_string()
+            }),
+        },
+        Directive::FileType => match &file.member {
+            Some(member) => member_type_char(member).to_string(),
+            None => fs::symlink_metadata(path)
+                .map(|m| type_char(&m).to_string())
+                .unwrap_or_else(|_| "U".to_string()),
+        },
+        _ => {
+            let Some(stat) = stat else {
+                return String::new();
+            };
+            render_stat_directive(directive, stat)
+        }
+    }
+}
+
+fn render_stat_directive(directive: &Directive, stat: &Stat) -> String {
+    let modified: DateTime<Local> = stat.modified.into();
+
+    match directive {
+        Directive::Size => stat.size.to_string(),
+        Directive::HumanSize => super::print::format_human_size(stat.size),
+        Directive::DateTime => modified.format("%Y-%m-%d %H:%M:%S").to_string(),
+        Directive::Time => modified.format("%H:%M:%S").to_string(),
+        Directive::Date => modified.format("%Y-%m-%d").to_string(),
+        Directive::CustomTime(fmt) => modified.format(fmt).to_string(),
+        Directive::Mode => format!("{:o}", stat.mode & 0o7777),
+        Directive::Permissions => permission_string(stat.type_char, stat.mode),
+        Directive::User => owner::user_name(stat.uid),
+        Directive::Group => owner::group_name(stat.gid),
+        Directive::Uid => stat.uid.to_string(),
+        Directive::Gid => stat.gid.to_string(),
+        Directive::Inode => stat.inode.to_string(),
+        Directive::Blocks => stat.kilo_blocks.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn member_type_char(member: &ArchiveMember) -> char {
+    match member.kind {
+        FilterType::Directory => 'd',
+        FilterType::Symlink => 'l',
+        _ => 'f',
+    }
+}
+
+/// find 风格的文件类型字符
+pub fn type_char(metadata: &Metadata) -> char {
+    let file_type = metadata.file_type();
+    if file_type.is_symlink() {
+        return 'l';
+    }
+    if file_type.is_dir() {
+        return 'd';
+    }
+    if file_type.is_file() {
+        return 'f';
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        if file_type.is_block_device() {
+            return 'b';
+        }
+        if file_type.is_char_device() {
+            return 'c';
+        }
+        if file_type.is_fifo() {
+            return 'p';
+        }
+        if file_type.is_socket() {
+            return 's';
+        }
+    }
+
+    'U'
+}
+
+/// 类型字符对应的名称，用于统计和 JSON 输出
+pub fn type_name(type_char: char) -> &'static str {
+    match type_char {
+        'f' => "file",
+        'd' => "directory",
+        'l' => "symlink",
+        'b' => "block device",
+        'c' => "char device",
+        'p' => "fifo",
+        's' => "socket",
+        _ => "unknown",
+    }
+}
+
+/// 生成 ls -l 风格的权限字符串，例如 `-rw-r--r--`
+pub fn permission_string(type_char: char, mode: u32) -> String {
+    let type_char = match type_char {
+        'f' => '-',
+        other => other,
+    };
+
+    let bit = |mask: u32, c: char| if mode & mask != 0 { c } else { '-' };
+    let special = |exec_mask: u32, special_mask: u32, set: char, unset: char| {
+        match (mode & exec_mask != 0, mode & special_mask != 0) {
+            (true, true) => set,
+            (false, true) => unset,
+            (true, false) => 'x',
+            (false, false) => '-',
+        }
+    };
+
+    [
+        type_char,
+        bit(0o400, 'r'),
+        bit(0o200, 'w'),
+        special(0o100, 0o4000, 's', 'S'),
+        bit(0o040, 'r'),
+        bit(0o020, 'w'),
+        special(0o010, 0o2000, 's', 'S'),
+        bit(0o004, 'r'),
+        bit(0o002, 'w'),
+        special(0o001, 0o1000, 't', 'T'),
+    ]
+    .iter()
+    .collect()
+}
+
+#[cfg(unix)]
+fn mode_bits(metadata: &Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode()
+}
+
+#[cfg(not(unix))]
+fn mode_bits(metadata: &Metadata) -> u32 {
+    if metadata.permissions().readonly() { 0o444 } else { 0o644 }
+}
+
+#[cfg(unix)]
+fn unix_ids(metadata: &Metadata) -> (u32, u32) {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.uid(), metadata.gid())
+}
+
+#[cfg(not(unix))]
+fn unix_ids(_metadata: &Metadata) -> (u32, u32) {
+    (0, 0)
+}
+
+#[cfg(unix)]
+fn inode(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.ino()
+}
+
+#[cfg(not(unix))]
+fn inode(_metadata: &Metadata) -> u64 {
+    0
+}
+
+/// 以 1K 为单位的磁盘占用块数
+#[cfg(unix)]
+fn kilo_blocks(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    // st_blocks 的单位固定为 512 字节
+    metadata.blocks().div_ceil(2)
+}
+
+#[cfg(not(unix))]
+fn kilo_blocks(metadata: &Metadata) -> u64 {
+    metadata.len().div_ceil(1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn found(path: PathBuf, depth: usize) -> FoundFile {
+        FoundFile { path, depth, member: None }
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_formats() {
+        assert!(matches!(PrintFormat::parse("%q"), Err(FindError::FormatError(_))));
+        assert!(matches!(PrintFormat::parse("abc %"), Err(FindError::FormatError(_))));
+        assert!(matches!(PrintFormat::parse("%-5"), Err(FindError::FormatError(_))));
+        assert!(matches!(PrintFormat::parse("%T{%Y"), Err(FindError::FormatError(_))));
+        assert!(matches!(PrintFormat::parse("%T{%Q}"), Err(FindError::FormatError(_))));
+        assert!(matches!(PrintFormat::parse("%H"), Err(FindError::FormatError(_))));
+        assert!(matches!(PrintFormat::parse("%H{crc32}"), Err(FindError::FormatError(_))));
+    }
+
+    #[test]
+    fn test_escapes_are_not_reexpanded() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("100%p.txt");
+        fs::write(&path, "x").unwrap();
+
+        let format = PrintFormat::parse("%f|%%p|a\\tb").unwrap();
+        let output = format.render(&found(path, 1), dir.path(), &PathStyle::AsFound).unwrap();
+        assert_eq!(output, "100%p.txt|%p|a\tb");
+    }
+
+    #[test]
+    fn test_width_and_alignment() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "hello").unwrap();
+
+        let format = PrintFormat::parse("[%-6f][%4s][%d]").unwrap();
+        let output = format.render(&found(path, 1), dir.path(), &PathStyle::AsFound).unwrap();
+        assert_eq!(output, "[a.txt ][   5][1]");
+    }
+
+    #[test]
+    fn test_relative_path_and_type() {
+        let dir = TempDir::new().unwrap();
+        let sub = dir.path().join("src");
+        fs::create_dir(&sub).unwrap();
+        let path = sub.join("main.rs");
+        fs::write(&path, "").unwrap();
+
+        let format = PrintFormat::parse("%P %y").unwrap();
+        assert_eq!(format.render(&found(path, 2), dir.path(), &PathStyle::AsFound).unwrap(), "src/main.rs f");
+        assert_eq!(format.render(&found(sub, 1), dir.path(), &PathStyle::AsFound).unwrap(), "src d");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_mode_and_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("script.sh");
+        fs::write(&path, "").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o754)).unwrap();
+
+        let format = PrintFormat::parse("%m %M").unwrap();
+        assert_eq!(format.render(&found(path, 1), dir.path(), &PathStyle::AsFound).unwrap(), "754 -rwxr-xr--");
+    }
+
+    #[test]
+    fn test_custom_time_format() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "").unwrap();
+
+        let format = PrintFormat::parse("%T{%Y}").unwrap();
+        let output = format.render(&found(path, 1), dir.path(), &PathStyle::AsFound).unwrap();
+        assert_eq!(output.len(), 4);
+        assert!(output.chars().all(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn test_hash_directive() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("abc.txt");
+        fs::write(&path, "abc").unwrap();
+
+        let format = PrintFormat::parse("%H{md5}  %f").unwrap();
+        assert!(format.has_hash());
+        assert_eq!(
+            format.render(&found(path, 1), dir.path(), &PathStyle::AsFound).unwrap(),
+            "900150983cd24fb0d6963f7d28e17f72  abc.txt"
+        );
+        assert_eq!(
+            format.render(&found(dir.path().to_path_buf(), 0), dir.path(), &PathStyle::AsFound).unwrap(),
+            format!("-  {}", dir.path().file_name().unwrap().to_string_lossy())
+        );
+    }
+}
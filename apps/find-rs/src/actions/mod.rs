@@ -1,38 +1,120 @@
-use std::path::PathBuf;
-use crate::types::{Cli, FindResult};
+use std::fs;
 
+use rayon::prelude::*;
+
+use crate::types::{Cli, FindResult, FoundFile, OutputFormat};
+
+pub mod archive;
+pub mod attributes;
+pub mod checksum;
+pub mod color;
 pub mod delete;
+pub mod du;
 pub mod exec;
+pub mod format;
+pub mod mode;
+pub mod owner;
+pub mod paths;
 pub mod print;
+pub mod relocate;
+pub mod stats;
+pub mod template;
 
-pub fn do_action(matches: &[PathBuf], cli: &Cli) -> FindResult<()> {
+pub fn do_action(matches: &[FoundFile], cli: &Cli) -> FindResult<()> {
     // 检查是否指定了任何操作
     let has_action = cli.actions.print.is_some() || 
                      cli.actions.exec.is_some() || 
-                     cli.actions.delete;
+                     cli.actions.checksum.is_some() ||
+                     cli.actions.archive.is_some() ||
+                     cli.actions.chmod.is_some() ||
+                     cli.actions.chown.is_some() ||
+                     cli.actions.touch.is_some() ||
+                     cli.actions.delete ||
+                     relocate::has_relocation(cli);
     
+    let style = paths::PathStyle::from_cli(cli)?;
+    let painter = color::Painter::from_cli(cli);
+
     if !has_action {
         // 如果没有指定动作，默认打印路径
-        for path in matches {
-            print::print_file(path, "%p")?;
+        if cli.actions.output == OutputFormat::Json {
+            for file in matches {
+                print::print_json(file, &style)?;
+            }
+            return Ok(());
+        }
+        if let Some(painter) = &painter {
+            for file in matches {
+                println!("{}", painter.paint(file, &style));
+            }
+            return Ok(());
+        }
+        let format = format::PrintFormat::parse("%p")?;
+        for file in matches {
+            print::print_file(file, &format, &cli.path, &style, None)?;
         }
         return Ok(());
     }
+
+    if let Some(algorithm) = cli.actions.checksum {
+        checksum::print_checksums(matches, algorithm)?;
+    }
+
+    // 先打包再执行其他操作，这样 --archive 可以和 --delete 组合成“备份后清理”
+    if let Some(output) = &cli.actions.archive {
+        archive::create_archive(matches, output, &cli.path, &style)?;
+    }
+
+    // 格式中包含摘要时需要读取整个文件，先并行渲染所有输出
+    let rendered = match &cli.actions.print {
+        Some(format) if format.has_hash() => Some(
+            matches
+                .par_iter()
+                .map(|file| format.render_painted(file, &cli.path, &style, painter.as_ref()))
+                .collect::<FindResult<Vec<_>>>()?,
+        ),
+        _ => None,
+    };
     
     // 执行指定操作
-    for path in matches {
-        if let Some(format) = &cli.actions.print {
-            print::print_file(path, format)?;
+    for (index, file) in matches.iter().enumerate() {
+        if let Some(lines) = &rendered {
+            println!("{}", lines[index]);
+        } else if let Some(format) = &cli.actions.print {
+            print::print_file(file, format, &cli.path, &style, painter.as_ref())?;
         }
         
         if let Some(cmd) = &cli.actions.exec {
-            exec::execute_command(cmd, path)?;
+            exec::execute_command(cmd, &style.display(file))?;
+        }
+
+        let updates_attributes = cli.actions.chmod.is_some()
+            || cli.actions.chown.is_some()
+            || cli.actions.touch.is_some();
+        if updates_attributes {
+            if file.member.is_some() {
+                eprintln!("Skipping attribute changes of archive member {}", file.path.display());
+            } else {
+                attributes::update_attributes(&file.path, &cli.actions);
+            }
         }
         
         if cli.actions.delete {
-            delete::delete_file(path, cli.actions.force)?;
+            // 压缩包成员只是虚拟路径，不能删除
+            if file.member.is_some() {
+                eprintln!("Skipping deletion of archive member {}", file.path.display());
+            } else if cli.criteria.empty && fs::symlink_metadata(&file.path).is_ok_and(|m| m.is_dir()) {
+                delete::delete_empty_dir(&file.path, cli.actions.force)?;
+            } else {
+                delete::delete_file(&file.path, cli.actions.force)?;
+            }
         }
     }
+
+    // 重命名/移动/复制需要先规划所有目标以检测冲突，因此在逐个处理之后统一执行
+    if relocate::has_relocation(cli) {
+        relocate::relocate(matches, cli)?;
+    }
     
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,180 @@
+use crate::types::{FindError, FindResult};
+
+// 各类用户对应的权限位（包括 setuid/setgid/sticky）
+const USER_BITS: u32 = 0o4700;
+const GROUP_BITS: u32 = 0o2070;
+const OTHER_BITS: u32 = 0o1007;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Add,
+    Remove,
+    Set,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Perms {
+    /// rwxXst 的组合，`X` 单独记录
+    Bits { bits: u32, cond_exec: bool },
+    /// 从 u/g/o 复制当前权限，例如 `g=u`
+    Copy(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Clause {
+    who: u32,
+    op: Op,
+    perms: Perms,
+}
+
+/// chmod 风格的权限描述，八进制（`644`）或符号形式（`u+x,go-w`、`a=rX`）
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModeSpec {
+    clauses: Vec<Clause>,
+}
+
+impl ModeSpec {
+    pub fn parse(spec: &str) -> FindResult<Self> {
+        let invalid = || FindError::Attribute(format!("invalid mode '{}'", spec));
+
+        if !spec.is_empty() && spec.chars().all(|c| c.is_digit(8)) {
+            let mode = u32::from_str_radix(spec, 8).map_err(|_| invalid())?;
+            if mode > 0o7777 {
+                return Err(invalid());
+            }
+            // 八进制形式相当于对所有权限位执行 `=`
+            let perms = Perms::Bits { bits: mode, cond_exec: false };
+            return Ok(ModeSpec { clauses: vec![Clause { who: 0o7777, op: Op::Set, perms }] });
+        }
+
+        let mut clauses = Vec::new();
+        for part in spec.split(',') {
+            let mut chars = part.chars().peekable();
+
+            let mut who = 0;
+            while let Some(c) = chars.next_if(|c| "ugoa".contains(*c)) {
+                who |= match c {
+                    'u' => USER_BITS,
+                    'g' => GROUP_BITS,
+                    'o' => OTHER_BITS,
+                    _ => USER_BITS | GROUP_BITS | OTHER_BITS,
+                };
+            }
+            // 省略用户类别时等同于 `a`
+            if who == 0 {
+                who = USER_BITS | GROUP_BITS | OTHER_BITS;
+            }
+
+            // 一个子句中可以包含多个操作，例如 `u+r-w`
+            let mut has_op = false;
+            while let Some(op) = chars.next() {
+                let op = match op {
+                    '+' => Op::Add,
+                    '-' => Op::Remove,
+                    '=' => Op::Set,
+                    _ => return Err(invalid()),
+                };
+
+                let perms = match chars.next_if(|c| "ugo".contains(*c)) {
+                    Some(source) => Perms::Copy(match source {
+                        'u' => USER_BITS,
+                        'g' => GROUP_BITS,
+                        _ => OTHER_BITS,
+                    }),
+                    None => {
+                        let mut bits = 0;
+                        let mut cond_exec = false;
+                        while let Some(c) = chars.next_if(|c| "rwxXst".contains(*c)) {
+                            match c {
+                                'r' => bits |= 0o444,
+                                'w' => bits |= 0o222,
+                                'x' => bits |= 0o111,
+                                'X' => cond_exec = true,
+                                's' => bits |= 0o6000,
+                                _ => bits |= 0o1000,
+                            }
+                        }
+                        Perms::Bits { bits, cond_exec }
+                    }
+                };
+
+                clauses.push(Clause { who, op, perms });
+                has_op = true;
+            }
+
+            if !has_op {
+                return Err(invalid());
+            }
+        }
+
+        Ok(ModeSpec { clauses })
+    }
+
+    /// 计算对 `mode` 应用后的权限位，`is_dir` 用于处理 `X`
+    pub fn apply(&self, mode: u32, is_dir: bool) -> u32 {
+        let mut mode = mode & 0o7777;
+        for clause in &self.clauses {
+            let bits = match clause.perms {
+                Perms::Bits { bits, cond_exec } => {
+                    // `X` 只对目录或已有执行位的文件添加执行权限
+                    if cond_exec && (is_dir || mode & 0o111 != 0) {
+                        bits | 0o111
+                    } else {
+                        bits
+                    }
+                }
+                Perms::Copy(source) => {
+                    let rwx = match source {
+                        USER_BITS => (mode >> 6) & 0o7,
+                        GROUP_BITS => (mode >> 3) & 0o7,
+                        _ => mode & 0o7,
+                    };
+                    rwx * 0o111
+                }
+            };
+            let bits = bits & clause.who;
+
+            mode = match clause.op {
+                Op::Add => mode | bits,
+                Op::Remove => mode & !bits,
+                Op::Set => (mode & !clause.who) | bits,
+            };
+        }
+        mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(spec: &str, mode: u32, is_dir: bool) -> u32 {
+        ModeSpec::parse(spec).unwrap().apply(mode, is_dir)
+    }
+
+    #[test]
+    fn test_octal_and_symbolic_modes() {
+        assert_eq!(apply("640", 0o777, false), 0o640);
+        assert_eq!(apply("o-w", 0o666, false), 0o664);
+        assert_eq!(apply("u+x,go-w", 0o666, false), 0o744);
+        assert_eq!(apply("a=r", 0o4755, false), 0o444);
+        assert_eq!(apply("u+r-w", 0o200, false), 0o400);
+        assert_eq!(apply("g=u", 0o740, false), 0o770);
+        assert_eq!(apply("+t", 0o755, true), 0o1755);
+        assert_eq!(apply("u+s", 0o755, false), 0o4755);
+    }
+
+    #[test]
+    fn test_conditional_execute() {
+        assert_eq!(apply("a+X", 0o644, false), 0o644);
+        assert_eq!(apply("a+X", 0o744, false), 0o755);
+        assert_eq!(apply("a+X", 0o700, true), 0o711);
+    }
+
+    #[test]
+    fn test_invalid_modes() {
+        for spec in ["", "8", "17777", "u", "u+q", "z+r", "u+r,"] {
+            assert!(ModeSpec::parse(spec).is_err(), "{spec}");
+        }
+    }
+}
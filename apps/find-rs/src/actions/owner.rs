@@ -0,0 +1,49 @@
+use std::{collections::HashMap, fs};
+
+use once_cell::sync::Lazy;
+
+// uid/gid 到名称的缓存，只在第一次使用时读取系统数据库
+static USERS: Lazy<HashMap<u32, String>> = Lazy::new(|| load_id_names("/etc/passwd"));
+static GROUPS: Lazy<HashMap<u32, String>> = Lazy::new(|| load_id_names("/etc/group"));
+
+/// 解析 passwd/group 格式的文件（name:x:id:...）
+fn load_id_names(path: &str) -> HashMap<u32, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let id = fields.nth(1)?.parse().ok()?;
+            Some((id, name.to_string()))
+        })
+        .collect()
+}
+
+/// 根据 uid 获取用户名，找不到时返回数字形式
+pub fn user_name(uid: u32) -> String {
+    USERS.get(&uid).cloned().unwrap_or_else(|| uid.to_string())
+}
+
+/// 根据 gid 获取组名，找不到时返回数字形式
+pub fn group_name(gid: u32) -> String {
+    GROUPS.get(&gid).cloned().unwrap_or_else(|| gid.to_string())
+}
+
+/// 根据用户名或数字获取 uid
+pub fn user_id(name: &str) -> Option<u32> {
+    name.parse()
+        .ok()
+        .or_else(|| USERS.iter().find(|(_, n)| *n == name).map(|(id, _)| *id))
+}
+
+/// 根据组名或数字获取 gid
+pub fn group_id(name: &str) -> Option<u32> {
+    name.parse()
+        .ok()
+        .or_else(|| GROUPS.iter().find(|(_, n)| *n == name).map(|(id, _)| *id))
+}
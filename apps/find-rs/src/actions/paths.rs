@@ -0,0 +1,134 @@
+use std::{
+    fs,
+    path::{self, Component, Path, PathBuf},
+};
+
+use crate::types::{ArchiveMember, Cli, FindError, FindResult, FoundFile};
+
+/// 输出路径的形式，由 `--absolute`、`--relative-to` 和 `--strip-root` 选择
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum PathStyle {
+    /// 遍历时得到的路径，即以搜索起点开头
+    #[default]
+    AsFound,
+    /// 规范化的绝对路径；最后一级不解析，符号链接仍显示为链接本身
+    Absolute,
+    /// 相对于给定目录（已规范化），必要时使用 `..`
+    RelativeTo(PathBuf),
+    /// 去掉搜索起点前缀，起点本身显示为 `.`
+    StripRoot(PathBuf),
+}
+
+impl PathStyle {
+    pub fn from_cli(cli: &Cli) -> FindResult<PathStyle> {
+        let actions = &cli.actions;
+        if actions.absolute {
+            return Ok(PathStyle::Absolute);
+        }
+        if let Some(dir) = &actions.relative_to {
+            let dir = fs::canonicalize(dir)
+                .map_err(|e| FindError::PathError(format!("{}: {}", dir.display(), e)))?;
+            return Ok(PathStyle::RelativeTo(dir));
+        }
+        if actions.strip_root {
+            return Ok(PathStyle::StripRoot(cli.path.clone()));
+        }
+        Ok(PathStyle::AsFound)
+    }
+
+    /// 匹配项用于输出的路径；压缩包成员只转换压缩包部分
+    pub fn display(&self, file: &FoundFile) -> PathBuf {
+        match &file.member {
+            Some(member) => ArchiveMember::join_virtual(&self.apply(&member.archive), &member.name),
+            None => self.apply(&file.path),
+        }
+    }
+
+    pub fn apply(&self, path: &Path) -> PathBuf {
+        match self {
+            PathStyle::AsFound => path.to_path_buf(),
+            PathStyle::Absolute => absolute(path),
+            PathStyle::RelativeTo(base) => relative_path(&absolute(path), base),
+            PathStyle::StripRoot(root) => match path.strip_prefix(root) {
+                Ok(relative) if relative.as_os_str().is_empty() => PathBuf::from("."),
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => path.to_path_buf(),
+            },
+        }
+    }
+}
+
+/// 规范化上级目录后拼接文件名；无法规范化时（例如路径已被删除）退回到词法上的绝对路径
+pub fn absolute(path: &Path) -> PathBuf {
+    let resolved = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => {
+            let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+            fs::canonicalize(parent).map(|parent| parent.join(name))
+        }
+        _ => fs::canonicalize(path),
+    };
+    resolved
+        .or_else(|_| path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// 计算绝对路径 `path` 相对于绝对路径 `base` 的相对路径
+pub fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let path_components: Vec<Component> = path.components().collect();
+    let base_components: Vec<Component> = base.components().collect();
+    let common = path_components
+        .iter()
+        .zip(&base_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..base_components.len() {
+        relative.push("..");
+    }
+    relative.extend(&path_components[common..]);
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    relative
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(relative_path(Path::new("/a/b/c"), Path::new("/a")), PathBuf::from("b/c"));
+        assert_eq!(relative_path(Path::new("/a/x"), Path::new("/a/b/c")), PathBuf::from("../../x"));
+        assert_eq!(relative_path(Path::new("/a"), Path::new("/a")), PathBuf::from("."));
+    }
+
+    #[test]
+    fn test_styles() {
+        let dir = TempDir::new().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        fs::create_dir(root.join("sub")).unwrap();
+        fs::write(root.join("sub/file"), "").unwrap();
+
+        // 通过含 `..` 的路径找到的条目也会被规范化
+        let found = root.join("sub/../sub/file");
+        assert_eq!(PathStyle::Absolute.apply(&found), root.join("sub/file"));
+        assert_eq!(
+            PathStyle::RelativeTo(root.join("sub")).apply(&found),
+            PathBuf::from("file")
+        );
+
+        let strip = PathStyle::StripRoot(root.clone());
+        assert_eq!(strip.apply(&root.join("sub/file")), PathBuf::from("sub/file"));
+        assert_eq!(strip.apply(&root), PathBuf::from("."));
+
+        // 失效的符号链接保持为链接本身的路径
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("missing", root.join("sub/link")).unwrap();
+            assert_eq!(PathStyle::Absolute.apply(&root.join("sub/link")), root.join("sub/link"));
+        }
+    }
+}
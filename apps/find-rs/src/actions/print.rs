@@ -1,54 +1,65 @@
-use std::{fs, path::Path, time::SystemTime};
+use std::{fs, path::Path};
+
 use chrono::{DateTime, Local};
-use crate::types::{FindResult};
+use serde::Serialize;
 
-pub fn print_file(path: &Path, format: &str) -> FindResult<()> {
-    let metadata = fs::metadata(path)?;
-    let filename = path.file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("");
-    
-    let parent = path.parent()
-        .unwrap_or_else(|| Path::new("."))
-        .to_str()
-        .unwrap_or("");
-    
-    let size = metadata.len();
-    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
-    
-    // 将 SystemTime 转换为可读格式
-    let datetime: DateTime<Local> = modified.into();
-    let time_str = datetime.format("%Y-%m-%d %H:%M:%S").to_string();
-    
-    // 支持的占位符映射
-    let mut output = format.to_string();
-    
-    // 一次性处理所有占位符替换
-    let replacements = [
-        ("%p", path.display().to_string()),
-        ("%f", filename.to_string()),
-        ("%d", parent.to_string()),
-        ("%s", format_size(size)),
-        ("%t", time_str),
-        ("%T", datetime.format("%H:%M:%S").to_string()),
-        ("%D", datetime.format("%Y-%m-%d").to_string()),
-        ("%h", format_human_size(size)),
-        ("%n", path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string()),
-    ];
-    
-    for (placeholder, replacement) in replacements {
-        output = output.replace(placeholder, &replacement);
-    }
-    
+use crate::{
+    actions::{
+        color::Painter,
+        format::{type_name, PrintFormat, Stat},
+        paths::PathStyle,
+    },
+    types::{FindError, FindResult, FoundFile},
+};
+
+/// `--output json` 时每个匹配项输出的字段
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    path: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    size: u64,
+    modified: DateTime<Local>,
+    mode: String,
+    depth: usize,
+    /// 压缩包成员所在的压缩包
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive: Option<std::borrow::Cow<'a, str>>,
+}
+
+pub fn print_file(
+    file: &FoundFile,
+    format: &PrintFormat,
+    root: &Path,
+    style: &PathStyle,
+    painter: Option<&Painter>,
+) -> FindResult<()> {
+    let output = format.render_painted(file, root, style, painter)?;
     println!("{}", output);
     Ok(())
 }
 
-fn format_size(bytes: u64) -> String {
-    bytes.to_string()
+/// 以 JSON Lines 格式输出一个匹配项，便于逐行流式处理
+pub fn print_json(file: &FoundFile, style: &PathStyle) -> FindResult<()> {
+    let stat = match &file.member {
+        Some(member) => Stat::from_member(member),
+        None => Stat::from_metadata(&fs::symlink_metadata(&file.path)?),
+    };
+    let entry = JsonEntry {
+        path: style.display(file).display().to_string(),
+        kind: type_name(stat.type_char),
+        size: stat.size,
+        modified: stat.modified.into(),
+        mode: format!("{:04o}", stat.mode & 0o7777),
+        depth: file.depth,
+        archive: file.member.as_ref().map(|member| member.archive.to_string_lossy()),
+    };
+    let line = serde_json::to_string(&entry).map_err(|e| FindError::FormatError(e.to_string()))?;
+    println!("{}", line);
+    Ok(())
 }
 
-fn format_human_size(bytes: u64) -> String {
+pub fn format_human_size(bytes: u64) -> String {
     const UNITS: [&str; 6] = ["B", "KB", "MB", "GB", "TB", "PB"];
     let mut size = bytes as f64;
     let mut unit_index = 0;
@@ -59,4 +70,4 @@ fn format_human_size(bytes: u64) -> String {
     }
     
     format!("{:.2} {}", size, UNITS[unit_index])
-}
\ No newline at end of file
+}
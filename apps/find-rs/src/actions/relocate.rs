@@ -0,0 +1,531 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use filetime::FileTime;
+use regex::Regex;
+
+use crate::{
+    actions::paths,
+    types::{Cli, FindError, FindResult, FoundFile},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mode {
+    Move,
+    Copy,
+}
+
+/// 一次重命名/移动/复制操作
+#[derive(Clone, Debug, PartialEq)]
+struct Operation {
+    source: PathBuf,
+    target: PathBuf,
+    is_dir: bool,
+}
+
+/// 已完成的操作，用于 all-or-nothing 模式下回滚
+enum Done {
+    /// 同一文件系统内的 rename，回滚时改回原名
+    Renamed { source: PathBuf, target: PathBuf },
+    /// 跨文件系统的复制后删除，回滚时把目标复制回原处再删除目标
+    MovedAcrossDevices { source: PathBuf, target: PathBuf },
+    /// 复制出的文件或符号链接（包括复制到一半的目录中的条目）
+    Copied(PathBuf),
+    CreatedDir(PathBuf),
+}
+
+pub fn has_relocation(cli: &Cli) -> bool {
+    cli.actions.rename.is_some() || cli.actions.move_to.is_some() || cli.actions.copy_to.is_some()
+}
+
+/// 执行 --rename / --move-to / --copy-to
+pub fn relocate(matches: &[FoundFile], cli: &Cli) -> FindResult<()> {
+    let actions = &cli.actions;
+    let mode = if actions.copy_to.is_some() { Mode::Copy } else { Mode::Move };
+    let base_dir = actions.copy_to.as_deref().or(actions.move_to.as_deref());
+
+    let regex = match (&actions.rename, &cli.criteria.regex) {
+        (Some(_), Some(pattern)) => {
+            let pattern = if cli.criteria.ignore_case(pattern, true) {
+                format!("(?i){}", pattern)
+            } else {
+                pattern.clone()
+            };
+            Some(Regex::new(&pattern)?)
+        }
+        _ => None,
+    };
+    if let Some(template) = &actions.rename {
+        template.validate_captures(regex.as_ref())?;
+    }
+
+    let operations = plan(matches, cli, mode, base_dir, regex.as_ref())?;
+    let conflicts = find_conflicts(&operations);
+
+    for (op, reason) in &conflicts {
+        eprintln!("Conflict: {} -> {}: {}", op.source.display(), op.target.display(), reason);
+    }
+
+    if actions.all_or_nothing && !conflicts.is_empty() {
+        return Err(FindError::Relocate(format!(
+            "{} conflict(s) found, nothing was changed",
+            conflicts.len()
+        )));
+    }
+
+    let conflicting: HashSet<&PathBuf> = conflicts.iter().map(|(op, _)| &op.source).collect();
+    let operations: Vec<&Operation> = operations
+        .iter()
+        .filter(|op| !conflicting.contains(&op.source))
+        .collect();
+
+    let verb = match (mode, base_dir) {
+        (Mode::Copy, _) => "copy",
+        (Mode::Move, Some(_)) => "move",
+        (Mode::Move, None) => "rename",
+    };
+
+    if actions.dry_run {
+        for op in &operations {
+            println!("[dry-run] {} {} -> {}", verb, op.source.display(), op.target.display());
+        }
+        return Ok(());
+    }
+
+    let mut done = Vec::new();
+    let mut failures = 0;
+    for op in &operations {
+        match apply(op, mode, &mut done) {
+            Ok(()) => println!("{} -> {}", op.source.display(), op.target.display()),
+            Err(e) => {
+                eprintln!("Failed to {} {}: {}", verb, op.source.display(), e);
+                if actions.all_or_nothing {
+                    rollback(done);
+                    return Err(FindError::Relocate(format!(
+                        "failed to {} {}, all changes were rolled back",
+                        verb,
+                        op.source.display()
+                    )));
+                }
+                failures += 1;
+            }
+        }
+    }
+
+    // 部分失败或因冲突跳过时以非零状态退出，脚本可以据此发现只完成了一部分
+    if failures > 0 || !conflicts.is_empty() {
+        return Err(FindError::Relocate(format!(
+            "{} operation(s) failed, {} skipped because of conflicts",
+            failures,
+            conflicts.len()
+        )));
+    }
+    Ok(())
+}
+
+fn plan(
+    matches: &[FoundFile],
+    cli: &Cli,
+    mode: Mode,
+    base_dir: Option<&Path>,
+    regex: Option<&Regex>,
+) -> FindResult<Vec<Operation>> {
+    // 搜索起点本身和压缩包成员无法被移动
+    let candidates: Vec<&FoundFile> = matches
+        .iter()
+        .filter(|file| file.depth > 0 && file.member.is_none())
+        .collect();
+    let moved_dirs: HashSet<&Path> = candidates
+        .iter()
+        .filter(|file| file.path.is_dir())
+        .map(|file| file.path.as_path())
+        .collect();
+
+    let mut operations = Vec::new();
+    for file in candidates {
+        // 移动或复制到其他目录时，父目录会带上其中的内容
+        if base_dir.is_some() && file.path.ancestors().skip(1).any(|a| moved_dirs.contains(a)) {
+            continue;
+        }
+
+        let file_name = file.path.file_name().unwrap_or_default();
+        let new_name = match &cli.actions.rename {
+            Some(template) => {
+                let name = file_name.to_string_lossy();
+                let captures = regex.and_then(|re| re.captures(&name));
+                PathBuf::from(template.render(&file.path, captures.as_ref())?)
+            }
+            None => PathBuf::from(file_name),
+        };
+
+        let parent = file.path.parent().unwrap_or(Path::new("."));
+        let target_dir = match base_dir {
+            // 保留相对于搜索起点的目录结构
+            Some(base) => base.join(parent.strip_prefix(&cli.path).unwrap_or(Path::new(""))),
+            None => parent.to_path_buf(),
+        };
+        let target = target_dir.join(new_name);
+        if base_dir.is_none() && target.parent() != file.path.parent() {
+            return Err(FindError::Relocate(format!(
+                "--rename would move {} out of its directory to {}",
+                file.path.display(),
+                target.display()
+            )));
+        }
+
+        if target != file.path {
+            operations.push(Operation {
+                source: file.path.clone(),
+                target,
+                is_dir: fs::symlink_metadata(&file.path).is_ok_and(|m| m.is_dir()),
+            });
+        }
+    }
+
+    // 原地重命名时先处理深层条目，避免父目录改名后子路径失效
+    if mode == Mode::Move && base_dir.is_none() {
+        operations.sort_by_key(|op| std::cmp::Reverse(op.source.components().count()));
+    }
+
+    Ok(operations)
+}
+
+fn find_conflicts(operations: &[Operation]) -> Vec<(Operation, String)> {
+    let mut by_target: HashMap<&Path, usize> = HashMap::new();
+    for op in operations {
+        *by_target.entry(op.target.as_path()).or_default() += 1;
+    }
+
+    operations
+        .iter()
+        .filter_map(|op| {
+            let reason = if by_target[op.target.as_path()] > 1 {
+                "several files map to the same target"
+            } else if fs::symlink_metadata(&op.target).is_ok() {
+                "target already exists"
+            } else if op.is_dir && resolve(&op.target).starts_with(paths::absolute(&op.source)) {
+                "cannot move or copy a directory into itself"
+            } else {
+                return None;
+            };
+            Some((op.clone(), reason.to_string()))
+        })
+        .collect()
+}
+
+/// 解析可能尚不存在的路径：规范化最近的已存在上级，其余部分按词法拼接
+fn resolve(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    for existing in absolute.ancestors() {
+        let Ok(base) = fs::canonicalize(existing) else {
+            continue;
+        };
+        let mut resolved = base;
+        for component in absolute.strip_prefix(existing).unwrap_or(Path::new("")).components() {
+            match component {
+                Component::ParentDir => {
+                    resolved.pop();
+                }
+                Component::Normal(name) => resolved.push(name),
+                _ => {}
+            }
+        }
+        return resolved;
+    }
+    absolute
+}
+
+fn apply(op: &Operation, mode: Mode, done: &mut Vec<Done>) -> io::Result<()> {
+    if let Some(parent) = op.target.parent() {
+        create_dirs(parent, done)?;
+    }
+
+    match mode {
+        Mode::Move => match fs::rename(&op.source, &op.target) {
+            Ok(()) => done.push(Done::Renamed { source: op.source.clone(), target: op.target.clone() }),
+            // 跨文件系统时退回到复制后删除
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+                let copied = done.len();
+                copy_recursive(&op.source, &op.target, done)?;
+                // 复制已完整完成，之后由移动记录负责回滚
+                done.truncate(copied);
+                done.push(Done::MovedAcrossDevices { source: op.source.clone(), target: op.target.clone() });
+                if op.is_dir {
+                    fs::remove_dir_all(&op.source)?;
+                } else {
+                    fs::remove_file(&op.source)?;
+                }
+            }
+            Err(e) => return Err(e),
+        },
+        // 目录连同其内容一起复制
+        Mode::Copy => copy_recursive(&op.source, &op.target, done)?,
+    }
+
+    Ok(())
+}
+
+/// 逐级创建缺失的目录，并记录下来以便回滚
+fn create_dirs(dir: &Path, done: &mut Vec<Done>) -> io::Result<()> {
+    let missing: Vec<&Path> = dir
+        .ancestors()
+        .take_while(|a| !a.as_os_str().is_empty() && !a.exists())
+        .collect();
+    for dir in missing.into_iter().rev() {
+        fs::create_dir(dir)?;
+        done.push(Done::CreatedDir(dir.to_path_buf()));
+    }
+    Ok(())
+}
+
+/// 复制单个文件或符号链接，保留权限和修改时间
+///
+/// 目标一经创建就记录下来，之后设置时间失败时回滚也能删除它。
+fn copy_entry(source: &Path, target: &Path, done: &mut Vec<Done>) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(source)?;
+
+    #[cfg(unix)]
+    if metadata.file_type().is_symlink() {
+        std::os::unix::fs::symlink(fs::read_link(source)?, target)?;
+        done.push(Done::Copied(target.to_path_buf()));
+        return Ok(());
+    }
+
+    fs::copy(source, target)?;
+    done.push(Done::Copied(target.to_path_buf()));
+    // 不重新打开目标文件，只读的副本同样可以设置时间
+    filetime::set_file_mtime(target, FileTime::from_last_modification_time(&metadata))
+}
+
+/// 递归复制，每个创建出的条目都会被记录，中途失败时可以回滚已复制的部分
+fn copy_recursive(source: &Path, target: &Path, done: &mut Vec<Done>) -> io::Result<()> {
+    if !fs::symlink_metadata(source)?.is_dir() {
+        return copy_entry(source, target, done);
+    }
+
+    fs::create_dir(target)?;
+    done.push(Done::CreatedDir(target.to_path_buf()));
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        copy_recursive(&entry.path(), &target.join(entry.file_name()), done)?;
+    }
+    Ok(())
+}
+
+/// 把 `from` 中原处已不存在的条目复制回 `to`，用于撤销跨文件系统的移动
+fn restore_missing(from: &Path, to: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(from)?;
+    if !metadata.is_dir() {
+        return match fs::symlink_metadata(to) {
+            Ok(_) => Ok(()),
+            // 恢复出的文件就是回滚的结果，不需要再记录
+            Err(_) => copy_entry(from, to, &mut Vec::new()),
+        };
+    }
+
+    if fs::symlink_metadata(to).is_err() {
+        fs::create_dir(to)?;
+    }
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        restore_missing(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// 按相反顺序撤销已完成的操作
+fn rollback(done: Vec<Done>) {
+    for step in done.into_iter().rev() {
+        let result = match &step {
+            Done::Renamed { source, target } => fs::rename(target, source),
+            Done::MovedAcrossDevices { source, target } => restore_missing(target, source).and_then(|()| {
+                if fs::symlink_metadata(target)?.is_dir() {
+                    fs::remove_dir_all(target)
+                } else {
+                    fs::remove_file(target)
+                }
+            }),
+            Done::Copied(target) => fs::remove_file(target),
+            Done::CreatedDir(dir) => fs::remove_dir(dir),
+        };
+        if let Err(e) = result {
+            eprintln!("Rollback failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    fn found(root: &Path, relative: &str) -> FoundFile {
+        FoundFile {
+            path: root.join(relative),
+            depth: Path::new(relative).components().count(),
+            member: None,
+        }
+    }
+
+    fn cli(root: &Path, args: &[&str]) -> Cli {
+        Cli::try_parse_from([&["find-rs", root.to_str().unwrap()], args].concat()).unwrap()
+    }
+
+    #[test]
+    fn test_copy_preserves_structure() {
+        let src = TempDir::new().unwrap();
+        let dst = TempDir::new().unwrap();
+        fs::create_dir(src.path().join("conf")).unwrap();
+        fs::write(src.path().join("conf/app.toml"), "a").unwrap();
+
+        let backup = dst.path().join("backup");
+        let cli = cli(src.path(), &["--copy-to", backup.to_str().unwrap()]);
+        relocate(&[found(src.path(), "conf/app.toml")], &cli).unwrap();
+
+        assert_eq!(fs::read_to_string(backup.join("conf/app.toml")).unwrap(), "a");
+        assert!(src.path().join("conf/app.toml").exists());
+    }
+
+    #[test]
+    fn test_copy_directory_with_contents() {
+        let src = TempDir::new().unwrap();
+        let dst = TempDir::new().unwrap();
+        fs::create_dir_all(src.path().join("conf/nested")).unwrap();
+        fs::write(src.path().join("conf/nested/app.toml"), "a").unwrap();
+
+        let backup = dst.path().join("backup");
+        let cli = cli(src.path(), &["--copy-to", backup.to_str().unwrap()]);
+        let matches = [found(src.path(), "conf"), found(src.path(), "conf/nested/app.toml")];
+        relocate(&matches, &cli).unwrap();
+
+        assert_eq!(fs::read_to_string(backup.join("conf/nested/app.toml")).unwrap(), "a");
+    }
+
+    #[test]
+    fn test_rollback_partial_copy_and_moves() {
+        let src = TempDir::new().unwrap();
+        let dst = TempDir::new().unwrap();
+        fs::create_dir(src.path().join("dir")).unwrap();
+        fs::write(src.path().join("dir/a"), "a").unwrap();
+        fs::write(src.path().join("b"), "b").unwrap();
+
+        // 复制到一半失败：已复制的条目也会被记录
+        let mut done = Vec::new();
+        copy_recursive(&src.path().join("dir"), &dst.path().join("dir"), &mut done).unwrap();
+        assert!(copy_recursive(&src.path().join("missing"), &dst.path().join("missing"), &mut done).is_err());
+
+        // 模拟一次跨文件系统的移动
+        copy_entry(&src.path().join("b"), &dst.path().join("b"), &mut Vec::new()).unwrap();
+        fs::remove_file(src.path().join("b")).unwrap();
+        done.push(Done::MovedAcrossDevices { source: src.path().join("b"), target: dst.path().join("b") });
+
+        rollback(done);
+        assert_eq!(fs::read_to_string(src.path().join("b")).unwrap(), "b");
+        assert_eq!(fs::read_dir(dst.path()).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_read_only_file_keeps_mtime() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let src = TempDir::new().unwrap();
+        let dst = TempDir::new().unwrap();
+        let source = src.path().join("ro.txt");
+        fs::write(&source, "a").unwrap();
+        filetime::set_file_mtime(&source, FileTime::from_unix_time(1_700_000_000, 0)).unwrap();
+        fs::set_permissions(&source, fs::Permissions::from_mode(0o444)).unwrap();
+
+        let mut done = Vec::new();
+        copy_entry(&source, &dst.path().join("ro.txt"), &mut done).unwrap();
+        assert_eq!(done.len(), 1);
+        let copied = fs::metadata(dst.path().join("ro.txt")).unwrap();
+        assert_eq!(FileTime::from_last_modification_time(&copied).unix_seconds(), 1_700_000_000);
+    }
+
+    #[test]
+    fn test_rename_with_regex_captures() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("log_2024.txt"), "").unwrap();
+
+        let cli = cli(dir.path(), &["-r", r"^log_(\d+)", "--rename", "{1}-{stem}.{ext}"]);
+        relocate(&[found(dir.path(), "log_2024.txt")], &cli).unwrap();
+
+        assert!(dir.path().join("2024-log_2024.txt").exists());
+        assert!(!dir.path().join("log_2024.txt").exists());
+    }
+
+    #[test]
+    fn test_cannot_copy_directory_into_itself() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("sub/inner")).unwrap();
+        fs::create_dir(dir.path().join("x")).unwrap();
+
+        // 源路径与目标路径写法不同，词法比较无法发现目标位于源目录之内
+        let source = FoundFile { path: dir.path().join("x/../sub"), depth: 1, member: None };
+        let backup = dir.path().join("sub/backup");
+        for action in ["--copy-to", "--move-to"] {
+            let cli = cli(dir.path(), &[action, backup.to_str().unwrap(), "--all-or-nothing"]);
+            assert!(matches!(relocate(std::slice::from_ref(&source), &cli), Err(FindError::Relocate(_))));
+        }
+        assert!(!backup.exists());
+    }
+
+    #[test]
+    fn test_rename_cannot_leave_directory() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("d/sub")).unwrap();
+        fs::write(dir.path().join("d/sub/a.txt"), "").unwrap();
+
+        for template in ["../../{name}", "/tmp/escaped-{name}"] {
+            let cli = cli(dir.path(), &["--rename", template, "--dry-run"]);
+            assert!(relocate(&[found(dir.path(), "d/sub/a.txt")], &cli).is_err());
+        }
+        assert!(dir.path().join("d/sub/a.txt").exists());
+    }
+
+    #[test]
+    fn test_conflicts_abort_in_all_or_nothing_mode() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "").unwrap();
+        fs::write(dir.path().join("b.txt"), "").unwrap();
+
+        let cli = cli(dir.path(), &["--rename", "same.txt", "--all-or-nothing"]);
+        let matches = [found(dir.path(), "a.txt"), found(dir.path(), "b.txt")];
+        assert!(matches!(relocate(&matches, &cli), Err(FindError::Relocate(_))));
+        assert!(dir.path().join("a.txt").exists());
+        assert!(dir.path().join("b.txt").exists());
+    }
+
+    #[test]
+    fn test_partial_failure_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "").unwrap();
+        fs::write(dir.path().join("b.txt"), "").unwrap();
+        fs::write(dir.path().join("taken.txt"), "").unwrap();
+
+        // b.txt 的目标已存在而被跳过，a.txt 仍然改名，但整体返回错误
+        let cli = cli(dir.path(), &["-r", "^(a|b)", "--rename", "{1}{1}.txt"]);
+        fs::rename(dir.path().join("taken.txt"), dir.path().join("bb.txt")).unwrap();
+        let matches = [found(dir.path(), "a.txt"), found(dir.path(), "b.txt")];
+        assert!(matches!(relocate(&matches, &cli), Err(FindError::Relocate(_))));
+        assert!(dir.path().join("aa.txt").exists());
+    }
+
+    #[test]
+    fn test_dry_run_changes_nothing() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "").unwrap();
+
+        let target = dir.path().join("moved");
+        let cli = cli(dir.path(), &["--move-to", target.to_str().unwrap(), "--dry-run"]);
+        relocate(&[found(dir.path(), "a.txt")], &cli).unwrap();
+
+        assert!(dir.path().join("a.txt").exists());
+        assert!(!target.exists());
+    }
+}
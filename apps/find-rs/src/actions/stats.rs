@@ -0,0 +1,275 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use crate::{
+    actions::{
+        format::{type_name, Stat},
+        owner,
+        print::format_human_size,
+    },
+    types::{Cli, FindError, FindResult, FoundFile, OutputFormat},
+};
+
+/// 最旧、最新和最深路径各保留的条目数
+const TOP_ENTRIES: usize = 5;
+
+/// 大小分布的区间下界和名称，最后一个区间没有上界
+const SIZE_BUCKETS: [(u64, &str); 8] = [
+    (0, "empty"),
+    (1, "< 1 KB"),
+    (1 << 10, "1 KB - 16 KB"),
+    (16 << 10, "16 KB - 256 KB"),
+    (256 << 10, "256 KB - 4 MB"),
+    (4 << 20, "4 MB - 64 MB"),
+    (64 << 20, "64 MB - 1 GB"),
+    (1 << 30, ">= 1 GB"),
+];
+
+/// `--stats` 的汇总结果；大小、扩展名、分布和新旧排行只统计非目录条目
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    pub entries: u64,
+    pub files: u64,
+    pub directories: u64,
+    pub total_size: u64,
+    pub by_type: Vec<Group>,
+    pub by_extension: Vec<Group>,
+    pub by_owner: Vec<Group>,
+    pub size_histogram: Vec<Bucket>,
+    pub oldest: Vec<Dated>,
+    pub newest: Vec<Dated>,
+    pub deepest: Vec<Deep>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Group {
+    pub key: String,
+    pub count: u64,
+    pub size: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Bucket {
+    pub label: &'static str,
+    pub min: u64,
+    /// 不包含的上界
+    pub max: Option<u64>,
+    pub count: u64,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Dated {
+    pub path: String,
+    pub modified: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Deep {
+    pub path: String,
+    pub depth: usize,
+}
+
+/// 按名称分组累加，输出时按大小、数量、名称排序
+#[derive(Default)]
+struct Grouper(HashMap<String, Group>);
+
+impl Grouper {
+    fn add(&mut self, key: &str, size: u64) {
+        let group = self.0.entry(key.to_string()).or_insert_with(|| Group {
+            key: key.to_string(),
+            ..Group::default()
+        });
+        group.count += 1;
+        group.size += size;
+    }
+
+    fn into_sorted(self) -> Vec<Group> {
+        let mut groups: Vec<Group> = self.0.into_values().collect();
+        groups.sort_by(|a, b| {
+            b.size.cmp(&a.size).then(b.count.cmp(&a.count)).then_with(|| a.key.cmp(&b.key))
+        });
+        groups
+    }
+}
+
+impl Stats {
+    pub fn collect(matches: &[FoundFile]) -> Stats {
+        let mut stats = Stats::default();
+        let (mut by_type, mut by_extension, mut by_owner) = (Grouper::default(), Grouper::default(), Grouper::default());
+        let mut histogram: Vec<Bucket> = SIZE_BUCKETS
+            .iter()
+            .enumerate()
+            .map(|(i, (min, label))| Bucket {
+                label,
+                min: *min,
+                max: SIZE_BUCKETS.get(i + 1).map(|(next, _)| *next),
+                count: 0,
+                size: 0,
+            })
+            .collect();
+        let mut dated = Vec::new();
+
+        for file in matches {
+            let stat = match &file.member {
+                Some(member) => Stat::from_member(member),
+                None => match fs::symlink_metadata(&file.path) {
+                    Ok(metadata) => Stat::from_metadata(&metadata),
+                    Err(e) => {
+                        eprintln!("Warning: Cannot stat {}: {}", file.path.display(), e);
+                        continue;
+                    }
+                },
+            };
+
+            stats.entries += 1;
+            by_type.add(type_name(stat.type_char), stat.size);
+            by_owner.add(&owner::user_name(stat.uid), stat.size);
+            if stat.type_char == 'd' {
+                stats.directories += 1;
+                continue;
+            }
+
+            stats.files += 1;
+            stats.total_size += stat.size;
+            by_extension.add(&extension_key(&file.path), stat.size);
+            if let Some(bucket) = histogram.iter_mut().rev().find(|bucket| stat.size >= bucket.min) {
+                bucket.count += 1;
+                bucket.size += stat.size;
+            }
+            dated.push((stat.modified, file));
+        }
+
+        stats.by_type = by_type.into_sorted();
+        stats.by_extension = by_extension.into_sorted();
+        stats.by_owner = by_owner.into_sorted();
+        stats.size_histogram = histogram;
+
+        let to_dated = |(modified, file): &(std::time::SystemTime, &FoundFile)| Dated {
+            path: file.path.display().to_string(),
+            modified: (*modified).into(),
+        };
+        dated.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.path.cmp(&b.1.path)));
+        stats.oldest = dated.iter().take(TOP_ENTRIES).map(to_dated).collect();
+        stats.newest = dated.iter().rev().take(TOP_ENTRIES).map(to_dated).collect();
+
+        let mut deepest: Vec<&FoundFile> = matches.iter().collect();
+        deepest.sort_by(|a, b| b.depth.cmp(&a.depth).then_with(|| a.path.cmp(&b.path)));
+        stats.deepest = deepest
+            .into_iter()
+            .take(TOP_ENTRIES)
+            .map(|file| Deep { path: file.path.display().to_string(), depth: file.depth })
+            .collect();
+
+        stats
+    }
+}
+
+/// 扩展名统一为小写，没有扩展名的归为一组
+fn extension_key(path: &Path) -> String {
+    match path.extension() {
+        Some(ext) => format!(".{}", ext.to_string_lossy().to_lowercase()),
+        None => "(none)".to_string(),
+    }
+}
+
+/// `--stats`：输出汇总而不是逐个列出匹配项
+pub fn print_stats(matches: &[FoundFile], cli: &Cli) -> FindResult<()> {
+    let stats = Stats::collect(matches);
+    match cli.actions.output {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&stats)
+                .map_err(|e| FindError::FormatError(e.to_string()))?;
+            println!("{}", json);
+        }
+        OutputFormat::Text => print_table(&stats),
+    }
+    Ok(())
+}
+
+fn print_table(stats: &Stats) {
+    println!(
+        "Entries: {} ({} files, {} directories), total size {}",
+        stats.entries,
+        stats.files,
+        stats.directories,
+        format_human_size(stats.total_size)
+    );
+
+    print_groups("Type", &stats.by_type);
+    print_groups("Extension", &stats.by_extension);
+    print_groups("Owner", &stats.by_owner);
+
+    println!("\n{:<16} {:>8} {:>12}", "Size", "Count", "Total");
+    for bucket in &stats.size_histogram {
+        println!("{:<16} {:>8} {:>12}", bucket.label, bucket.count, format_human_size(bucket.size));
+    }
+
+    for (title, entries) in [("Oldest", &stats.oldest), ("Newest", &stats.newest)] {
+        if !entries.is_empty() {
+            println!("\n{} files", title);
+            for entry in entries {
+                println!("  {}  {}", entry.modified.format("%Y-%m-%d %H:%M:%S"), entry.path);
+            }
+        }
+    }
+
+    if !stats.deepest.is_empty() {
+        println!("\nDeepest paths");
+        for entry in &stats.deepest {
+            println!("  {:>4}  {}", entry.depth, entry.path);
+        }
+    }
+}
+
+fn print_groups(title: &str, groups: &[Group]) {
+    if groups.is_empty() {
+        return;
+    }
+    let width = groups.iter().map(|g| g.key.chars().count()).chain([title.len()]).max().unwrap_or(0);
+    println!("\n{:<width$} {:>8} {:>12}", title, "Count", "Size", width = width);
+    for group in groups {
+        println!(
+            "{:<width$} {:>8} {:>12}",
+            group.key,
+            group.count,
+            format_human_size(group.size),
+            width = width
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("sub")).unwrap();
+        fs::write(root.join("a.LOG"), vec![0; 2000]).unwrap();
+        fs::write(root.join("sub/b.log"), vec![0; 10]).unwrap();
+        fs::write(root.join("sub/README"), "").unwrap();
+
+        let matches: Vec<FoundFile> = [("sub", 1), ("a.LOG", 1), ("sub/b.log", 2), ("sub/README", 2)]
+            .iter()
+            .map(|(p, depth)| FoundFile { path: root.join(p), depth: *depth, member: None })
+            .collect();
+        let stats = Stats::collect(&matches);
+
+        assert_eq!((stats.entries, stats.files, stats.directories, stats.total_size), (4, 3, 1, 2010));
+        assert_eq!(stats.by_extension[0], Group { key: ".log".to_string(), count: 2, size: 2010 });
+        assert_eq!(stats.by_extension[1].key, "(none)");
+        assert_eq!(stats.by_type.iter().map(|g| g.count).sum::<u64>(), 4);
+
+        let counts: Vec<u64> = stats.size_histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, [1, 1, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(stats.size_histogram.last().unwrap().max, None);
+        assert_eq!(stats.deepest[0].depth, 2);
+        assert_eq!(stats.oldest.len(), 3);
+    }
+}
@@ -0,0 +1,235 @@
+use std::{fs, path::Path, time::SystemTime};
+
+use chrono::{DateTime, Local};
+use regex::{Captures, Regex};
+
+use crate::{
+    actions::format::validate_time_format,
+    types::{FindError, FindResult},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Literal(String),
+    /// 不含扩展名的文件名
+    Stem,
+    /// 不含点号的扩展名
+    Ext,
+    /// 完整文件名
+    Name,
+    Mtime(String),
+    CaptureIndex(usize),
+    CaptureName(String),
+}
+
+/// `--rename` 使用的文件名模板，例如 `{stem}-{mtime:%Y%m%d}.{ext}` 或 `{1}_{2}.log`
+///
+/// `{N}` 与 `{NAME}` 引用 `--regex` 的捕获组，`{{` 和 `}}` 表示字面量花括号。
+#[derive(Clone, Debug, PartialEq)]
+pub struct NameTemplate {
+    tokens: Vec<Token>,
+}
+
+impl NameTemplate {
+    pub fn parse(template: &str) -> FindResult<Self> {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.next_if_eq(&'{').is_some() => literal.push('{'),
+                '}' if chars.next_if_eq(&'}').is_some() => literal.push('}'),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => {
+                                return Err(FindError::FormatError(format!(
+                                    "unterminated placeholder '{{{}' in rename template",
+                                    placeholder
+                                )))
+                            }
+                        }
+                    }
+
+                    if !literal.is_empty() {
+                        tokens.push(Token::Literal(std::mem::take(&mut literal)));
+                    }
+                    tokens.push(parse_placeholder(&placeholder)?);
+                }
+                '}' => {
+                    return Err(FindError::FormatError(
+                        "unmatched '}' in rename template (use '}}' for a literal brace)".to_string(),
+                    ))
+                }
+                _ => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(literal));
+        }
+
+        Ok(NameTemplate { tokens })
+    }
+
+    /// 检查模板引用的捕获组在正则中确实存在
+    pub fn validate_captures(&self, regex: Option<&Regex>) -> FindResult<()> {
+        for token in &self.tokens {
+            let missing = match (token, regex) {
+                (Token::CaptureIndex(index), Some(regex)) => *index >= regex.captures_len(),
+                (Token::CaptureName(name), Some(regex)) => !regex.capture_names().flatten().any(|n| n == name),
+                (Token::CaptureIndex(_) | Token::CaptureName(_), None) => true,
+                _ => false,
+            };
+
+            if missing {
+                return Err(FindError::FormatError(format!(
+                    "rename template references capture group {:?} not present in --regex",
+                    token
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// 为一个文件生成新文件名，`captures` 来自对文件名的正则匹配
+    pub fn render(&self, path: &Path, captures: Option<&Captures>) -> FindResult<String> {
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let stem = path
+            .file_stem()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let ext = path
+            .extension()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let mut output = String::new();
+        for token in &self.tokens {
+            match token {
+                Token::Literal(text) => output.push_str(text),
+                Token::Stem => output.push_str(&stem),
+                Token::Ext => output.push_str(&ext),
+                Token::Name => output.push_str(&file_name),
+                Token::Mtime(fmt) => {
+                    let modified = fs::symlink_metadata(path)?.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    let modified: DateTime<Local> = modified.into();
+                    output.push_str(&modified.format(fmt).to_string());
+                }
+                Token::CaptureIndex(index) => {
+                    if let Some(m) = captures.and_then(|c| c.get(*index)) {
+                        output.push_str(m.as_str());
+                    }
+                }
+                Token::CaptureName(name) => {
+                    if let Some(m) = captures.and_then(|c| c.name(name)) {
+                        output.push_str(m.as_str());
+                    }
+                }
+            }
+        }
+
+        // 只能在原目录内改名：不允许路径分隔符（因此也不会是绝对路径）
+        let has_separator = output.contains(['/', std::path::MAIN_SEPARATOR]);
+        if output.is_empty() || output == "." || output == ".." || has_separator {
+            return Err(FindError::FormatError(format!(
+                "rename template produced an invalid name '{}' for {}",
+                output,
+                path.display()
+            )));
+        }
+
+        Ok(output)
+    }
+}
+
+fn parse_placeholder(placeholder: &str) -> FindResult<Token> {
+    if let Some(fmt) = placeholder.strip_prefix("mtime:") {
+        validate_time_format(fmt)?;
+        return Ok(Token::Mtime(fmt.to_string()));
+    }
+
+    let token = match placeholder {
+        "stem" => Token::Stem,
+        "ext" => Token::Ext,
+        "name" => Token::Name,
+        "mtime" => Token::Mtime("%Y%m%d".to_string()),
+        _ if !placeholder.is_empty() && placeholder.chars().all(|c| c.is_ascii_digit()) => {
+            let index = placeholder.parse().map_err(|_| {
+                FindError::FormatError(format!("invalid capture index '{{{}}}'", placeholder))
+            })?;
+            Token::CaptureIndex(index)
+        }
+        _ if !placeholder.is_empty() && placeholder.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+            Token::CaptureName(placeholder.to_string())
+        }
+        _ => {
+            return Err(FindError::FormatError(format!(
+                "invalid placeholder '{{{}}}' in rename template",
+                placeholder
+            )))
+        }
+    };
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_placeholders() {
+        let template = NameTemplate::parse("{stem}_bak.{ext}").unwrap();
+        assert_eq!(template.render(Path::new("dir/report.txt"), None).unwrap(), "report_bak.txt");
+
+        let template = NameTemplate::parse("{{{name}}}").unwrap();
+        assert_eq!(template.render(Path::new("a.rs"), None).unwrap(), "{a.rs}");
+    }
+
+    #[test]
+    fn test_capture_groups() {
+        let regex = Regex::new(r"^IMG_(\d{4})(?<month>\d{2})\.jpg$").unwrap();
+        let template = NameTemplate::parse("{1}-{month}.jpg").unwrap();
+        template.validate_captures(Some(&regex)).unwrap();
+
+        let captures = regex.captures("IMG_202401.jpg").unwrap();
+        assert_eq!(
+            template.render(Path::new("IMG_202401.jpg"), Some(&captures)).unwrap(),
+            "2024-01.jpg"
+        );
+
+        assert!(NameTemplate::parse("{3}").unwrap().validate_captures(Some(&regex)).is_err());
+        assert!(NameTemplate::parse("{day}").unwrap().validate_captures(Some(&regex)).is_err());
+        assert!(NameTemplate::parse("{1}").unwrap().validate_captures(None).is_err());
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        assert!(NameTemplate::parse("{stem").is_err());
+        assert!(NameTemplate::parse("a}b").is_err());
+        assert!(NameTemplate::parse("{a-b}").is_err());
+        assert!(NameTemplate::parse("{mtime:%Q}").is_err());
+        assert!(NameTemplate::parse("{ext}").unwrap().render(Path::new("Makefile"), None).is_err());
+    }
+
+    #[test]
+    fn test_rendered_name_stays_in_directory() {
+        for template in ["../../{name}", "/tmp/escaped-{name}", "sub/{name}", "..", "."] {
+            let template = NameTemplate::parse(template).unwrap();
+            assert!(template.render(Path::new("d/sub/a.txt"), None).is_err());
+        }
+
+        // 捕获组的内容同样受检查
+        let regex = Regex::new(r"^(.*)$").unwrap();
+        let captures = regex.captures("x/../y").unwrap();
+        let template = NameTemplate::parse("{1}").unwrap();
+        assert!(template.render(Path::new("a.txt"), Some(&captures)).is_err());
+    }
+}
@@ -0,0 +1,320 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{parser::ValueSource, ArgMatches, CommandFactory, FromArgMatches};
+use serde::Deserialize;
+
+use crate::{
+    snapshot,
+    types::{Cli, ColorChoice, Command, FindError, FindResult, OutputFormat, SnapshotCommand},
+};
+
+/// 项目内配置文件名，从当前目录向上查找最近的一个
+const PROJECT_CONFIG: &str = ".find-rs.toml";
+
+/// 配置文件内容
+///
+/// ```toml
+/// [defaults]
+/// hidden = true
+/// exclude = ["target", "node_modules"]
+///
+/// [searches.stale-logs]
+/// description = "Logs untouched for a month"
+/// args = ["/var/log", "--name", "*.log", "--mtime", "+30d"]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub defaults: Defaults,
+    pub searches: BTreeMap<String, SavedSearch>,
+}
+
+/// 搜索条件的默认值，命令行上显式给出的选项优先
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Defaults {
+    /// 对应 `--all`
+    pub hidden: Option<bool>,
+    pub follow_symlinks: Option<bool>,
+    pub one_file_system: Option<bool>,
+    pub max_depth: Option<usize>,
+    pub output: Option<OutputFormat>,
+    pub color: Option<ColorChoice>,
+    /// 列表类选项与命令行上的值合并
+    pub exclude: Vec<String>,
+    pub hidden_patterns: Vec<String>,
+}
+
+/// 以 `find-rs @name` 调用的命名搜索，`args` 与命令行参数写法相同
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedSearch {
+    pub description: Option<String>,
+    pub args: Vec<String>,
+}
+
+impl Config {
+    /// 依次读取用户配置和项目配置，后者覆盖前者
+    pub fn load() -> FindResult<Config> {
+        let mut config = Config::default();
+        let cwd = env::current_dir()?;
+        for path in user_config_path().into_iter().chain(project_config_path(&cwd)) {
+            if path.is_file() {
+                config.merge(Config::from_file(&path)?);
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> FindResult<Config> {
+        let text = fs::read_to_string(path)?;
+        Config::parse(&text).map_err(|e| FindError::Config(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn parse(text: &str) -> Result<Config, String> {
+        toml::from_str(text).map_err(|e| e.message().to_string())
+    }
+
+    /// 合并优先级更高的配置：单值选项被覆盖，列表追加，同名搜索被替换
+    pub fn merge(&mut self, other: Config) {
+        let (defaults, other_defaults) = (&mut self.defaults, other.defaults);
+        defaults.hidden = other_defaults.hidden.or(defaults.hidden);
+        defaults.follow_symlinks = other_defaults.follow_symlinks.or(defaults.follow_symlinks);
+        defaults.one_file_system = other_defaults.one_file_system.or(defaults.one_file_system);
+        defaults.max_depth = other_defaults.max_depth.or(defaults.max_depth);
+        defaults.output = other_defaults.output.or(defaults.output);
+        defaults.color = other_defaults.color.or(defaults.color);
+        defaults.exclude.extend(other_defaults.exclude);
+        defaults.hidden_patterns.extend(other_defaults.hidden_patterns);
+        self.searches.extend(other.searches);
+    }
+
+    /// 把第一个参数 `@name` 展开为命名搜索的参数，其后的命令行参数保留在后面，
+    /// 因此可以覆盖命名搜索中的同名选项
+    pub fn expand_search(&self, mut args: Vec<OsString>) -> FindResult<Vec<OsString>> {
+        let Some(name) = args.get(1).and_then(|arg| arg.to_str()).and_then(|arg| arg.strip_prefix('@')) else {
+            return Ok(args);
+        };
+
+        let Some(search) = self.searches.get(name) else {
+            if self.searches.is_empty() {
+                return Err(FindError::Config(format!("unknown saved search '@{}' (no searches are configured)", name)));
+            }
+            let available: Vec<String> = self
+                .searches
+                .iter()
+                .map(|(name, search)| match &search.description {
+                    Some(description) => format!("  @{:<16} {}", name, description),
+                    None => format!("  @{}", name),
+                })
+                .collect();
+            return Err(FindError::Config(format!(
+                "unknown saved search '@{}', available searches:\n{}",
+                name,
+                available.join("\n")
+            )));
+        };
+
+        args.splice(1..2, search.args.iter().map(OsString::from));
+        Ok(args)
+    }
+
+    /// 把默认值填入没有在命令行上给出的选项
+    pub fn apply_defaults(&self, cli: &mut Cli, matches: &ArgMatches) {
+        let from_cli = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+        let defaults = &self.defaults;
+        let criteria = &mut cli.criteria;
+
+        // --all 与 --hidden-pattern 互斥，命令行给出隐藏模式时忽略配置中的 hidden；
+        // --no-* 选项与对应选项互相覆盖，以最后出现的为准，两者都会屏蔽配置中的值
+        if let Some(hidden) = defaults
+            .hidden
+            .filter(|_| !from_cli("all") && !criteria.no_all && !from_cli("hidden_patterns"))
+        {
+            criteria.all = hidden;
+        }
+        if let Some(follow) = defaults.follow_symlinks.filter(|_| !from_cli("follow_symlinks") && !criteria.no_follow) {
+            criteria.follow_symlinks = follow;
+        }
+        if let Some(one_file_system) = defaults
+            .one_file_system
+            .filter(|_| !from_cli("one_file_system") && !criteria.no_one_file_system)
+        {
+            criteria.one_file_system = one_file_system;
+        }
+        if let Some(depth) = defaults.max_depth.filter(|_| !from_cli("depth") && !criteria.no_max_depth) {
+            criteria.depth = Some(depth);
+        }
+        if !criteria.no_config_excludes {
+            criteria.excludes.extend(defaults.exclude.iter().cloned());
+        }
+        if !criteria.all {
+            criteria.hidden_patterns.extend(defaults.hidden_patterns.iter().cloned());
+        }
+
+        // 显式的 --print 格式优先于配置中的输出格式
+        if let Some(output) = defaults.output.filter(|_| !from_cli("output") && !from_cli("print")) {
+            cli.actions.output = output;
+        }
+        if let Some(color) = defaults.color.filter(|_| !from_cli("color")) {
+            cli.actions.color = color;
+        }
+    }
+}
+
+/// 解析命令行，先展开 `@name` 命名搜索，再用配置文件补全未给出的选项
+pub fn parse_cli() -> FindResult<Cli> {
+    let args: Vec<OsString> = env::args_os().collect();
+    let config = if wants_no_config(&args) {
+        Config::default()
+    } else {
+        Config::load()?
+    };
+
+    let matches = Cli::command().get_matches_from(config.expand_search(args)?);
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    config.apply_defaults(&mut cli, &matches);
+
+    // 快照记录保存时的搜索条件，diff 与当前文件系统比较时按同样的条件搜索
+    if let Some(Command::Snapshot { command: SnapshotCommand::Save { args, .. } }) = &mut cli.command {
+        if let Some(save) = matches.subcommand_matches("snapshot").and_then(|m| m.subcommand_matches("save")) {
+            *args = snapshot::criteria_args(save);
+        }
+    }
+    Ok(cli)
+}
+
+/// 由 clap 判断是否给出了 `--no-config`，作为其他选项的值或出现在 `--` 之后时不算
+///
+/// 此时 `@name` 尚未展开，其余参数的错误也留到正式解析时报告，
+/// 因此所有取值都不做校验，避免解析在出错处提前停止。
+fn wants_no_config(args: &[OsString]) -> bool {
+    Cli::command()
+        .mut_args(|arg| {
+            if arg.get_action().takes_values() {
+                arg.value_parser(clap::value_parser!(OsString))
+            } else {
+                arg
+            }
+        })
+        .ignore_errors(true)
+        .try_get_matches_from(args)
+        .is_ok_and(|matches| matches.get_one::<bool>("no_config").copied().unwrap_or(false))
+}
+
+/// `$XDG_CONFIG_HOME/find-rs/config.toml`，未设置时使用 `~/.config`
+fn user_config_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("find-rs").join("config.toml"))
+}
+
+fn project_config_path(cwd: &Path) -> Option<PathBuf> {
+    cwd.ancestors().map(|dir| dir.join(PROJECT_CONFIG)).find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: &str = r#"
+        [defaults]
+        hidden = true
+        follow_symlinks = true
+        exclude = ["target"]
+        output = "json"
+
+        [searches.logs]
+        args = ["/var/log", "--name", "*.log"]
+    "#;
+
+    fn parse(config: &Config, args: &[&str]) -> Cli {
+        let args = config.expand_search(args.iter().map(OsString::from).collect()).unwrap();
+        let matches = Cli::command().try_get_matches_from(args).unwrap();
+        let mut cli = Cli::from_arg_matches(&matches).unwrap();
+        config.apply_defaults(&mut cli, &matches);
+        cli
+    }
+
+    #[test]
+    fn test_merge_and_defaults() {
+        let mut config = Config::parse(USER).unwrap();
+        config.merge(Config::parse("[defaults]\nhidden = false\nexclude = [\"dist\"]").unwrap());
+
+        let cli = parse(&config, &["find-rs", "."]);
+        assert!(!cli.criteria.all);
+        assert!(cli.criteria.follow_symlinks);
+        assert_eq!(cli.criteria.excludes, ["target", "dist"]);
+        assert_eq!(cli.actions.output, OutputFormat::Json);
+
+        // 命令行上给出的选项优先
+        let cli = parse(&config, &["find-rs", ".", "--all", "--output", "text", "-E", "*.tmp"]);
+        assert!(cli.criteria.all);
+        assert_eq!(cli.actions.output, OutputFormat::Text);
+        assert_eq!(cli.criteria.excludes, ["*.tmp", "target", "dist"]);
+    }
+
+    #[test]
+    fn test_negating_flags_override_defaults() {
+        let config = Config::parse(
+            "[defaults]\nhidden = true\nfollow_symlinks = true\none_file_system = true\nmax_depth = 3\nexclude = [\"target\"]",
+        )
+        .unwrap();
+
+        let cli = parse(&config, &["find-rs", "."]);
+        assert!(cli.criteria.all && cli.criteria.follow_symlinks && cli.criteria.one_file_system);
+        assert_eq!(cli.criteria.depth, Some(3));
+
+        let cli = parse(&config, &["find-rs", ".", "--no-all"]);
+        assert!(!cli.criteria.all);
+        let cli = parse(&config, &["find-rs", ".", "--no-follow"]);
+        assert!(!cli.criteria.follow_symlinks);
+        let cli = parse(&config, &["find-rs", ".", "--no-one-file-system"]);
+        assert!(!cli.criteria.one_file_system);
+        let cli = parse(&config, &["find-rs", ".", "--no-max-depth"]);
+        assert_eq!(cli.criteria.depth, None);
+        let cli = parse(&config, &["find-rs", ".", "--no-config-excludes", "-E", "*.tmp"]);
+        assert_eq!(cli.criteria.excludes, ["*.tmp"]);
+
+        // 以最后出现的为准
+        let cli = parse(&config, &["find-rs", ".", "--no-all", "--all", "--follow-symlinks", "--no-follow"]);
+        assert!(cli.criteria.all);
+        assert!(!cli.criteria.follow_symlinks);
+        let cli = parse(&config, &["find-rs", ".", "--no-max-depth", "--depth", "1"]);
+        assert_eq!(cli.criteria.depth, Some(1));
+    }
+
+    #[test]
+    fn test_saved_search() {
+        let config = Config::parse(USER).unwrap();
+        let cli = parse(&config, &["find-rs", "@logs", "--name", "*.gz"]);
+        assert_eq!(cli.path, PathBuf::from("/var/log"));
+        assert_eq!(cli.criteria.name.as_deref(), Some("*.gz"));
+
+        let err = config.expand_search(vec!["find-rs".into(), "@nope".into()]).unwrap_err();
+        assert!(err.to_string().contains("@logs"));
+    }
+
+    #[test]
+    fn test_no_config_detection() {
+        let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+        assert!(wants_no_config(&args(&["find-rs", ".", "--no-config"])));
+        assert!(wants_no_config(&args(&["find-rs", "@logs", "--no-config"])));
+        assert!(!wants_no_config(&args(&["find-rs", ".", "--name=--no-config"])));
+        assert!(!wants_no_config(&args(&["find-rs", "--", "--no-config"])));
+        assert!(wants_no_config(&args(&["find-rs", ".", "--size", "bogus", "--no-config"])));
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        assert!(Config::parse("[defaults]\nhiden = true").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    collections::HashMap,
+    fs::{self, Metadata},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{
+    index::{Index, IndexEntry},
+    types::{FilterType, FindError, FindResult},
+};
+
+/// 构建统计，用于报告增量刷新的效果
+#[derive(Debug, Default, PartialEq)]
+pub struct BuildStats {
+    /// 重新读取了内容的目录数
+    pub rescanned: usize,
+    /// 修改时间未变、直接复用旧记录的目录数
+    pub reused: usize,
+}
+
+/// 上一次索引中每个目录的修改时间和直接子项
+struct Previous<'a> {
+    dirs: HashMap<&'a Path, (SystemTime, Vec<&'a IndexEntry>)>,
+}
+
+impl<'a> Previous<'a> {
+    fn new(index: Option<&'a Index>) -> Self {
+        let mut dirs: HashMap<&Path, (SystemTime, Vec<&IndexEntry>)> = HashMap::new();
+        let Some(index) = index else {
+            return Previous { dirs };
+        };
+
+        for entry in &index.entries {
+            if entry.kind == FilterType::Directory {
+                dirs.entry(&entry.path).or_insert((entry.modified, Vec::new())).0 = entry.modified;
+            }
+            if let Some(parent) = entry.path.parent().filter(|_| !entry.path.as_os_str().is_empty()) {
+                dirs.entry(parent).or_insert((SystemTime::UNIX_EPOCH, Vec::new())).1.push(entry);
+            }
+        }
+        Previous { dirs }
+    }
+}
+
+struct Builder<'a> {
+    root: PathBuf,
+    previous: Previous<'a>,
+    /// 不跨越挂载点时记录根目录的设备号
+    device: Option<u64>,
+    entries: Vec<IndexEntry>,
+    stats: BuildStats,
+}
+
+/// 为 `root` 建立索引
+///
+/// 传入旧索引时只重新读取修改时间发生变化的目录，其余目录直接复用旧的子项记录，
+/// 因此未变目录中文件的大小和修改时间可能是旧值，需要时可用完整重建刷新。
+pub fn build(root: &Path, previous: Option<&Index>, one_file_system: bool) -> FindResult<(Index, BuildStats)> {
+    let root = fs::canonicalize(root)?;
+    let metadata = fs::metadata(&root)?;
+    if !metadata.is_dir() {
+        return Err(FindError::Index(format!("{} is not a directory", root.display())));
+    }
+
+    // 根目录不同的旧索引没有复用价值
+    let previous = previous.filter(|index| index.root == root);
+    let mut builder = Builder {
+        previous: Previous::new(previous),
+        device: if one_file_system { device_of(&metadata) } else { None },
+        root: root.clone(),
+        entries: Vec::new(),
+        stats: BuildStats::default(),
+    };
+
+    let built = SystemTime::now();
+    builder.scan_dir(PathBuf::new(), &metadata);
+
+    Ok((Index { root, built, entries: builder.entries }, builder.stats))
+}
+
+impl Builder<'_> {
+    fn scan_dir(&mut self, relative: PathBuf, metadata: &Metadata) {
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        self.entries.push(entry_from(relative.clone(), metadata));
+
+        // 目录修改时间不变说明其中没有增删或重命名，只需继续检查子目录
+        let reusable = self
+            .previous
+            .dirs
+            .get(relative.as_path())
+            .filter(|(previous_mtime, _)| *previous_mtime == modified)
+            .map(|(_, children)| children.clone());
+
+        match reusable {
+            Some(children) => {
+                self.stats.reused += 1;
+                for child in children {
+                    if child.kind == FilterType::Directory {
+                        self.visit(child.path.clone());
+                    } else {
+                        self.entries.push(child.clone());
+                    }
+                }
+            }
+            None => {
+                self.stats.rescanned += 1;
+                let mut names = match fs::read_dir(self.root.join(&relative)) {
+                    Ok(dir) => dir.filter_map(|entry| entry.ok()).map(|entry| entry.file_name()).collect::<Vec<_>>(),
+                    Err(e) => {
+                        eprintln!("Warning: Cannot read directory {}: {}", self.root.join(&relative).display(), e);
+                        return;
+                    }
+                };
+                // 与搜索时的遍历顺序保持一致
+                names.sort();
+                for name in names {
+                    self.visit(relative.join(name));
+                }
+            }
+        }
+    }
+
+    fn visit(&mut self, relative: PathBuf) {
+        let metadata = match fs::symlink_metadata(self.root.join(&relative)) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                eprintln!("Warning: Cannot stat {}: {}", self.root.join(&relative).display(), e);
+                return;
+            }
+        };
+
+        let other_device = self.device.is_some() && device_of(&metadata) != self.device;
+        if metadata.is_dir() && !other_device {
+            self.scan_dir(relative, &metadata);
+        } else {
+            self.entries.push(entry_from(relative, &metadata));
+        }
+    }
+}
+
+fn entry_from(path: PathBuf, metadata: &Metadata) -> IndexEntry {
+    let file_type = metadata.file_type();
+    let kind = if file_type.is_dir() {
+        FilterType::Directory
+    } else if file_type.is_symlink() {
+        FilterType::Symlink
+    } else {
+        FilterType::File
+    };
+
+    IndexEntry {
+        path,
+        kind,
+        size: metadata.len(),
+        modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+        mode: mode_of(metadata),
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn mode_of(metadata: &Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+pub(crate) fn mode_of(metadata: &Metadata) -> u32 {
+    if metadata.permissions().readonly() { 0o444 } else { 0o644 }
+}
+
+#[cfg(unix)]
+fn device_of(metadata: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn device_of(_metadata: &Metadata) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs::File, time::Duration};
+    use tempfile::TempDir;
+
+    fn paths(index: &Index) -> Vec<String> {
+        index.entries.iter().map(|e| e.path.to_string_lossy().into_owned()).collect()
+    }
+
+    fn set_mtime(path: &Path, secs: u64) {
+        File::open(path)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_incremental_refresh() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        fs::create_dir(dir.path().join("c")).unwrap();
+        fs::write(dir.path().join("a/b/x.txt"), "x").unwrap();
+        fs::write(dir.path().join("c/y.txt"), "y").unwrap();
+        for sub in ["a", "a/b", "c", ""] {
+            set_mtime(&dir.path().join(sub), 1_000_000);
+        }
+
+        let (first, stats) = build(dir.path(), None, false).unwrap();
+        assert_eq!(paths(&first), ["", "a", "a/b", "a/b/x.txt", "c", "c/y.txt"]);
+        assert_eq!(stats, BuildStats { rescanned: 4, reused: 0 });
+
+        // 只有 c 发生了变化，其余目录复用旧记录
+        fs::write(dir.path().join("c/z.txt"), "z").unwrap();
+        let (second, stats) = build(dir.path(), Some(&first), false).unwrap();
+        assert_eq!(paths(&second), ["", "a", "a/b", "a/b/x.txt", "c", "c/y.txt", "c/z.txt"]);
+        assert_eq!(stats, BuildStats { rescanned: 1, reused: 3 });
+
+        // 删除的目录会从父目录的重新扫描中消失
+        fs::remove_dir_all(dir.path().join("a")).unwrap();
+        let (third, _) = build(dir.path(), Some(&second), false).unwrap();
+        assert_eq!(paths(&third), ["", "c", "c/y.txt", "c/z.txt"]);
+    }
+
+    #[test]
+    fn test_root_must_be_directory() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file");
+        fs::write(&file, "").unwrap();
+        assert!(matches!(build(&file, None, false), Err(FindError::Index(_))));
+    }
+}
@@ -0,0 +1,330 @@
+use std::{
+    env,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::types::{FilterType, FindError, FindResult, IndexCommand};
+
+pub mod build;
+
+const MAGIC: &[u8; 8] = b"FRSIDX\0\x01";
+
+/// 索引中的一个条目，路径相对于索引根目录（根目录本身为空路径）
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexEntry {
+    pub path: PathBuf,
+    /// 只会是 File、Directory 或 Symlink
+    pub kind: FilterType,
+    pub size: u64,
+    pub modified: SystemTime,
+    pub mode: u32,
+}
+
+/// 持久化的路径数据库，条目按目录先序、同级按文件名排序
+#[derive(Clone, Debug, PartialEq)]
+pub struct Index {
+    /// 规范化后的根目录
+    pub root: PathBuf,
+    pub built: SystemTime,
+    pub entries: Vec<IndexEntry>,
+}
+
+impl Index {
+    /// 读取索引文件
+    ///
+    /// 格式：魔数、构建时间、根目录和条目数，之后每个条目依次为与上一条路径的
+    /// 公共前缀长度、剩余部分、类型、大小、修改时间和权限位，数字均为变长编码。
+    pub fn load(path: &Path) -> FindResult<Self> {
+        let mut reader = BufReader::new(File::open(path).map_err(|e| {
+            FindError::Index(format!("cannot open index {}: {}", path.display(), e))
+        })?);
+        let corrupt = || FindError::Index(format!("{}: corrupt or incompatible index file", path.display()));
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).map_err(|_| corrupt())?;
+        if &magic != MAGIC {
+            return Err(corrupt());
+        }
+
+        let mut decode = || -> std::io::Result<Index> {
+            let built = read_time(&mut reader)?;
+            let root = path_from_bytes(read_bytes(&mut reader)?);
+            let count = read_varint(&mut reader)? as usize;
+
+            let mut entries = Vec::with_capacity(count.min(1 << 20));
+            let mut previous: Vec<u8> = Vec::new();
+            for _ in 0..count {
+                let shared = read_varint(&mut reader)? as usize;
+                let suffix = read_bytes(&mut reader)?;
+                if shared > previous.len() {
+                    return Err(std::io::ErrorKind::InvalidData.into());
+                }
+                previous.truncate(shared);
+                previous.extend_from_slice(&suffix);
+
+                let kind = match read_u8(&mut reader)? {
+                    b'd' => FilterType::Directory,
+                    b'l' => FilterType::Symlink,
+                    _ => FilterType::File,
+                };
+                entries.push(IndexEntry {
+                    path: path_from_bytes(previous.clone()),
+                    kind,
+                    size: read_varint(&mut reader)?,
+                    modified: read_time(&mut reader)?,
+                    mode: read_varint(&mut reader)? as u32,
+                });
+            }
+
+            Ok(Index { root, built, entries })
+        };
+
+        decode().map_err(|_| corrupt())
+    }
+
+    /// 先写入临时文件再重命名，避免查询读到写了一半的索引
+    pub fn save(&self, path: &Path) -> FindResult<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let temp = path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&temp)?);
+        writer.write_all(MAGIC)?;
+        write_time(&mut writer, self.built)?;
+        write_bytes(&mut writer, self.root.as_os_str().as_encoded_bytes())?;
+        write_varint(&mut writer, self.entries.len() as u64)?;
+
+        let mut previous: &[u8] = &[];
+        for entry in &self.entries {
+            let bytes = entry.path.as_os_str().as_encoded_bytes();
+            let shared = previous.iter().zip(bytes).take_while(|(a, b)| a == b).count();
+            write_varint(&mut writer, shared as u64)?;
+            write_bytes(&mut writer, &bytes[shared..])?;
+            writer.write_all(match entry.kind {
+                FilterType::Directory => b"d",
+                FilterType::Symlink => b"l",
+                _ => b"f",
+            })?;
+            write_varint(&mut writer, entry.size)?;
+            write_time(&mut writer, entry.modified)?;
+            write_varint(&mut writer, entry.mode as u64)?;
+            previous = bytes;
+        }
+
+        writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+}
+
+/// 执行 `find-rs index` 子命令
+pub fn run(command: &IndexCommand) -> FindResult<()> {
+    match command {
+        IndexCommand::Build { root, output, full, one_file_system } => {
+            let root = fs::canonicalize(root)?;
+            let output = match output {
+                Some(output) => output.clone(),
+                None => default_index_path(&root)?,
+            };
+
+            // 旧索引损坏或不存在时退回到完整构建
+            let previous = if *full { None } else { Index::load(&output).ok() };
+            let (index, stats) = build::build(&root, previous.as_ref(), *one_file_system)?;
+            index.save(&output)?;
+
+            eprintln!(
+                "Indexed {} entries under {} ({} of {} directories re-read) into {}",
+                index.entries.len(),
+                index.root.display(),
+                stats.rescanned,
+                stats.rescanned + stats.reused,
+                output.display()
+            );
+            Ok(())
+        }
+    }
+}
+
+/// 根目录对应的默认索引文件：`$XDG_CACHE_HOME/find-rs/index-<hash>.db`
+pub fn default_index_path(root: &Path) -> FindResult<PathBuf> {
+    let cache = env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .ok_or_else(|| FindError::Index("cannot determine cache directory (set HOME or XDG_CACHE_HOME)".to_string()))?;
+
+    let hash = blake3::hash(root.as_os_str().as_encoded_bytes());
+    Ok(cache
+        .join("find-rs")
+        .join(format!("index-{}.db", &hash.to_hex()[..16])))
+}
+
+/// 为搜索路径找到覆盖它的索引：显式指定的文件，或者最近的已建立索引的上级目录
+pub fn load_for(search_path: &Path, index_file: Option<&Path>) -> FindResult<Index> {
+    let search_path = fs::canonicalize(search_path)?;
+
+    let index = match index_file {
+        Some(file) => Index::load(file)?,
+        None => {
+            let file = search_path
+                .ancestors()
+                .map(default_index_path)
+                .collect::<FindResult<Vec<_>>>()?
+                .into_iter()
+                .find(|file| file.is_file())
+                .ok_or_else(|| {
+                    FindError::Index(format!(
+                        "no index covers {}; run 'find-rs index build <root>' first",
+                        search_path.display()
+                    ))
+                })?;
+            Index::load(&file)?
+        }
+    };
+
+    if !search_path.starts_with(&index.root) {
+        return Err(FindError::Index(format!(
+            "{} is outside the indexed root {}",
+            search_path.display(),
+            index.root.display()
+        )));
+    }
+    Ok(index)
+}
+
+fn write_varint(writer: &mut impl Write, mut value: u64) -> std::io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint(reader: &mut impl Read) -> std::io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = read_u8(reader)?;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(std::io::ErrorKind::InvalidData.into())
+}
+
+fn read_u8(reader: &mut impl Read) -> std::io::Result<u8> {
+    let mut byte = [0u8];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn write_bytes(writer: &mut impl Write, bytes: &[u8]) -> std::io::Result<()> {
+    write_varint(writer, bytes.len() as u64)?;
+    writer.write_all(bytes)
+}
+
+fn read_bytes(reader: &mut impl Read) -> std::io::Result<Vec<u8>> {
+    let len = read_varint(reader)? as usize;
+    let mut bytes = Vec::new();
+    reader.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+/// 时间按 Unix 纪元之后的秒和纳秒保存，更早的时间记为纪元
+fn write_time(writer: &mut impl Write, time: SystemTime) -> std::io::Result<()> {
+    let since_epoch = time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+    write_varint(writer, since_epoch.as_secs())?;
+    write_varint(writer, since_epoch.subsec_nanos() as u64)
+}
+
+fn read_time(reader: &mut impl Read) -> std::io::Result<SystemTime> {
+    let secs = read_varint(reader)?;
+    let nanos = read_varint(reader)?;
+    Ok(SystemTime::UNIX_EPOCH + Duration::new(secs, nanos.min(999_999_999) as u32))
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(path: &str, kind: FilterType, size: u64) -> IndexEntry {
+        IndexEntry {
+            path: PathBuf::from(path),
+            kind,
+            size,
+            modified: SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 123),
+            mode: 0o644,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("nested/index.db");
+        let index = Index {
+            root: PathBuf::from("/srv/data"),
+            built: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_100),
+            entries: vec![
+                entry("", FilterType::Directory, 0),
+                entry("docs", FilterType::Directory, 0),
+                entry("docs/readme.md", FilterType::File, 1234),
+                entry("docs/reference.md", FilterType::File, u64::MAX),
+                entry("link", FilterType::Symlink, 9),
+            ],
+        };
+
+        index.save(&file).unwrap();
+        assert_eq!(Index::load(&file).unwrap(), index);
+    }
+
+    #[test]
+    fn test_rejects_foreign_files() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("index.db");
+        fs::write(&file, "not an index").unwrap();
+        assert!(matches!(Index::load(&file), Err(FindError::Index(_))));
+
+        fs::write(&file, [MAGIC.as_slice(), &[0x80]].concat()).unwrap();
+        assert!(matches!(Index::load(&file), Err(FindError::Index(_))));
+    }
+
+    #[test]
+    fn test_default_path_is_per_root() {
+        let a = default_index_path(Path::new("/a")).unwrap();
+        let b = default_index_path(Path::new("/b")).unwrap();
+        assert_ne!(a, b);
+        assert!(a.to_string_lossy().contains("find-rs"));
+    }
+
+    #[test]
+    fn test_varint_boundaries() {
+        for value in [0, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value).unwrap();
+            assert_eq!(read_varint(&mut bytes.as_slice()).unwrap(), value);
+        }
+    }
+}
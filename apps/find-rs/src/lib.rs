@@ -2,52 +2,80 @@ use std::time::Instant;
 
 mod matcher;
 mod actions;
+mod config;
+mod index;
+mod snapshot;
+mod tui;
 mod types;
 
 pub use types::{Cli, FindError, FindResult};
 
+use tracing_subscriber::EnvFilter;
+use types::Command;
+
+/// 日志级别由 `FIND_RS_LOG` 控制（例如 `debug`、`find_rs=trace`），
+/// 旧的 `FIND_RS_DEBUG` 仍然可用，等同于 `debug`
+fn init_tracing() {
+    let filter = match std::env::var("FIND_RS_LOG") {
+        Ok(directives) => EnvFilter::new(directives),
+        Err(_) if std::env::var_os("FIND_RS_DEBUG").is_some() => EnvFilter::new("debug"),
+        Err(_) => EnvFilter::new("warn"),
+    };
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .without_time()
+        .try_init();
+}
+
 pub fn execute() -> FindResult<()> {
+    init_tracing();
     let start_time = Instant::now();
     
-    let cli = matcher::parser::parse_cli();
+    let cli = config::parse_cli()?;
+    tracing::debug!(?cli, "parsed arguments");
     
-    // 调试：打印解析后的参数
-    if std::env::var("FIND_RS_DEBUG").is_ok() {
-        eprintln!("Debug: CLI args: {:?}", cli);
-        eprintln!("Debug: Searching in: {}", cli.path.display());
-        if let Some(ref name) = cli.criteria.name {
-            eprintln!("Debug: Name pattern: '{}'", name);
-        }
-        if let Some(ref filter_type) = cli.criteria.filter_type {
-            eprintln!("Debug: Filter type: {:?}", filter_type);
-        }
+    match &cli.command {
+        Some(Command::Index { command }) => return index::run(command),
+        Some(Command::Snapshot { command }) => return snapshot::run(command),
+        None => {}
     }
-    
-    let walker = matcher::walker::get_walker(&cli);
-    
-    if std::env::var("FIND_RS_DEBUG").is_ok() {
-        eprintln!("Debug: Walker created, starting search...");
+
+    // --verify 只校验清单，不进行搜索
+    if let Some(manifest) = &cli.actions.verify {
+        return actions::checksum::verify_manifest(manifest, cli.actions.checksum);
+    }
+
+    if cli.actions.interactive {
+        return tui::picker::run(&cli);
     }
     
-    let matches = matcher::finder::find_files(walker, &cli)?;
+    let matches = if cli.criteria.from_index {
+        let index = index::load_for(&cli.path, cli.criteria.index_file.as_deref())?;
+        matcher::finder::find_in_index(&index, &cli)?
+    } else {
+        tracing::debug!(path = %cli.path.display(), "starting search");
+        matcher::finder::find_files(&cli)?
+    };
     
-    if matches.is_empty() {
+    // 统计结果即使为空也要输出，方便下游程序统一处理
+    if cli.actions.stats {
+        actions::stats::print_stats(&matches, &cli)?;
+    } else if matches.is_empty() {
         eprintln!("No files found matching the given criteria");
-        
-        // 调试：提供一些建议
-        if std::env::var("FIND_RS_DEBUG").is_ok() {
-            eprintln!("Debug: Consider using --all to include hidden files");
-            eprintln!("Debug: Consider checking the search path: {}", cli.path.display());
-            eprintln!("Debug: Consider using a simpler pattern");
+        tracing::debug!("consider --all for hidden files, checking the search path, or --explain to see why entries were rejected");
+    } else if cli.actions.du {
+        if cli.actions.tui {
+            tui::du::run(&matches, &cli)?;
+        } else {
+            actions::du::print_usage(&matches, &cli)?;
         }
     } else {
         eprintln!("Found {} matching file(s)", matches.len());
         actions::do_action(&matches, &cli)?;
     }
     
-    if std::env::var("FIND_RS_DEBUG").is_ok() {
-        eprintln!("Execution completed in {:?}", start_time.elapsed());
-    }
+    tracing::debug!(elapsed = ?start_time.elapsed(), "execution completed");
     
     Ok(())
 }
\ No newline at end of file
@@ -1,3 +1,6 @@
 fn main() {
-    find_rs::execute().unwrap();
+    if let Err(e) = find_rs::execute() {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
 }
\ No newline at end of file
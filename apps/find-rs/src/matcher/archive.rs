@@ -0,0 +1,206 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use chrono::{Local, NaiveDateTime};
+use flate2::read::GzDecoder;
+
+use crate::types::{ArchiveMember, FilterType, FindError, FindResult};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ArchiveFormat {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// 根据扩展名判断压缩包格式，避免对每个文件都读取内容
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".zip") || name.ends_with(".jar") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    ArchiveFormat::from_path(path).is_some()
+}
+
+/// 列出压缩包中的所有成员（不解压内容）
+pub fn read_members(path: &Path) -> FindResult<Vec<ArchiveMember>> {
+    let format = ArchiveFormat::from_path(path)
+        .ok_or_else(|| FindError::Archive(format!("{}: unsupported archive format", path.display())))?;
+    let file = BufReader::new(File::open(path)?);
+
+    match format {
+        ArchiveFormat::Tar => read_tar(path, file),
+        ArchiveFormat::TarGz => read_tar(path, GzDecoder::new(file)),
+        ArchiveFormat::Zip => read_zip(path, file),
+    }
+}
+
+fn read_tar<R: Read>(archive: &Path, reader: R) -> FindResult<Vec<ArchiveMember>> {
+    let mut tar = tar::Archive::new(reader);
+    let mut members = Vec::new();
+
+    for entry in tar.entries()? {
+        let entry = entry?;
+        let header = entry.header();
+        let entry_type = header.entry_type();
+
+        let kind = if entry_type.is_dir() {
+            FilterType::Directory
+        } else if entry_type.is_symlink() {
+            FilterType::Symlink
+        } else if entry_type.is_file() || entry_type.is_hard_link() {
+            FilterType::File
+        } else {
+            // 设备文件、扩展头等不作为成员输出
+            continue;
+        };
+
+        // 压缩包根目录 "./" 本身不作为成员
+        let name = clean_member_name(&entry.path()?);
+        if name.as_os_str().is_empty() {
+            continue;
+        }
+
+        members.push(ArchiveMember {
+            archive: archive.to_path_buf(),
+            name,
+            size: header.size().unwrap_or(0),
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(header.mtime().unwrap_or(0)),
+            mode: header.mode().map(|m| m & 0o7777).unwrap_or(0o644),
+            uid: header.uid().unwrap_or(0) as u32,
+            gid: header.gid().unwrap_or(0) as u32,
+            kind,
+            link_target: entry.link_name()?.map(|target| target.into_owned()),
+        });
+    }
+
+    Ok(members)
+}
+
+fn read_zip<R: Read + std::io::Seek>(archive: &Path, reader: R) -> FindResult<Vec<ArchiveMember>> {
+    let mut zip = zip::ZipArchive::new(reader).map_err(|e| FindError::Archive(e.to_string()))?;
+    let mut members = Vec::with_capacity(zip.len());
+
+    for index in 0..zip.len() {
+        let file = zip
+            .by_index_raw(index)
+            .map_err(|e| FindError::Archive(e.to_string()))?;
+
+        let kind = if file.is_dir() {
+            FilterType::Directory
+        } else if file.is_symlink() {
+            FilterType::Symlink
+        } else {
+            FilterType::File
+        };
+
+        // zip 中保存的是不带时区的本地时间
+        let modified = file
+            .last_modified()
+            .and_then(|dt| NaiveDateTime::try_from(dt).ok())
+            .and_then(|dt| dt.and_local_timezone(Local).single())
+            .map(SystemTime::from)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+
+        let default_mode = if kind == FilterType::Directory { 0o755 } else { 0o644 };
+
+        let name = clean_member_name(Path::new(file.name()));
+        if name.as_os_str().is_empty() {
+            continue;
+        }
+
+        members.push(ArchiveMember {
+            archive: archive.to_path_buf(),
+            name,
+            size: file.size(),
+            modified,
+            mode: file.unix_mode().map(|m| m & 0o7777).unwrap_or(default_mode),
+            uid: 0,
+            gid: 0,
+            kind,
+            link_target: None,
+        });
+    }
+
+    Ok(members)
+}
+
+/// 去掉成员路径开头的 `./` 和 `/`，以及目录末尾的斜杠
+fn clean_member_name(name: &Path) -> PathBuf {
+    name.components()
+        .filter(|c| matches!(c, std::path::Component::Normal(_)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_archive_format_detection() {
+        assert_eq!(ArchiveFormat::from_path(Path::new("a/bundle.tar.gz")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::from_path(Path::new("x.TGZ")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::from_path(Path::new("x.tar")), Some(ArchiveFormat::Tar));
+        assert_eq!(ArchiveFormat::from_path(Path::new("x.zip")), Some(ArchiveFormat::Zip));
+        assert_eq!(ArchiveFormat::from_path(Path::new("x.gz")), None);
+    }
+
+    #[test]
+    fn test_read_tar_gz_members() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bundle.tar.gz");
+
+        let encoder = flate2::write::GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o755);
+        header.set_mtime(1_700_000_000);
+        header.set_cksum();
+        builder.append_data(&mut header, "./lib/foo.so", &b"hello"[..]).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let members = read_members(&path).unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].name, PathBuf::from("lib/foo.so"));
+        assert_eq!(members[0].size, 5);
+        assert_eq!(members[0].mode, 0o755);
+        assert_eq!(members[0].kind, FilterType::File);
+        assert_eq!(members[0].virtual_path(), dir.path().join("bundle.tar.gz!/lib/foo.so"));
+    }
+
+    #[test]
+    fn test_read_zip_members() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bundle.zip");
+
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        writer.add_directory("docs/", zip::write::SimpleFileOptions::default()).unwrap();
+        writer.start_file("docs/readme.txt", zip::write::SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"read me").unwrap();
+        writer.finish().unwrap();
+
+        let members = read_members(&path).unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].kind, FilterType::Directory);
+        assert_eq!(members[1].name, PathBuf::from("docs/readme.txt"));
+        assert_eq!(members[1].size, 7);
+    }
+}
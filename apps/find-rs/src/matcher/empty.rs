@@ -0,0 +1,62 @@
+use std::{collections::HashSet, fs, path::{Path, PathBuf}};
+
+/// 匹配空文件（长度为 0）或空目录
+///
+/// `pending` 中的路径视为已经删除：后序遍历配合 `--delete` 时，
+/// 只包含待删除条目的目录同样算作空目录，从而一次遍历就能清理嵌套的空目录树；
+/// 子项可能在确认时被保留，所以删除时会重新检查（见 `delete::delete_empty_dir`）。
+pub fn match_empty(path: &Path, pending: &HashSet<PathBuf>) -> bool {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return false;
+    };
+
+    if metadata.is_file() {
+        return metadata.len() == 0;
+    }
+
+    if metadata.is_dir() {
+        return match fs::read_dir(path) {
+            Ok(mut entries) => entries.all(|entry| {
+                entry.is_ok_and(|e| pending.contains(&e.path()))
+            }),
+            Err(_) => false,
+        };
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_empty_files_and_dirs() {
+        let dir = TempDir::new().unwrap();
+        let empty_file = dir.path().join("empty.txt");
+        let full_file = dir.path().join("full.txt");
+        let empty_dir = dir.path().join("empty_dir");
+        fs::write(&empty_file, "").unwrap();
+        fs::write(&full_file, "data").unwrap();
+        fs::create_dir(&empty_dir).unwrap();
+
+        let pending = HashSet::new();
+        assert!(match_empty(&empty_file, &pending));
+        assert!(!match_empty(&full_file, &pending));
+        assert!(match_empty(&empty_dir, &pending));
+        assert!(!match_empty(dir.path(), &pending));
+    }
+
+    #[test]
+    fn test_pending_children_count_as_removed() {
+        let dir = TempDir::new().unwrap();
+        let nested = dir.path().join("a/b");
+        fs::create_dir_all(&nested).unwrap();
+
+        let mut pending = HashSet::new();
+        assert!(!match_empty(&dir.path().join("a"), &pending));
+        pending.insert(nested);
+        assert!(match_empty(&dir.path().join("a"), &pending));
+    }
+}
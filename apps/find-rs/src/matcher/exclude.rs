@@ -0,0 +1,79 @@
+use std::path::{Path, PathBuf};
+
+use walkdir::DirEntry;
+
+use crate::{matcher::name::NamePattern, types::Cli};
+
+/// `--exclude` 规则：匹配文件名或相对于搜索起点的路径
+#[derive(Clone, Debug)]
+pub struct ExcludeRules {
+    root: PathBuf,
+    patterns: Vec<NamePattern>,
+}
+
+impl ExcludeRules {
+    pub fn from_cli(cli: &Cli) -> Self {
+        ExcludeRules {
+            root: cli.path.clone(),
+            patterns: compile(&cli.criteria.excludes),
+        }
+    }
+
+    /// 被排除的目录不会被打开，搜索起点本身永远不排除
+    pub fn is_excluded(&self, entry: &DirEntry) -> bool {
+        entry.depth() > 0 && self.is_excluded_path(entry.path())
+    }
+
+    pub fn is_excluded_path(&self, path: &Path) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+
+        let name = path.file_name().and_then(|n| n.to_str());
+        let relative = path
+            .strip_prefix(&self.root)
+            .ok()
+            .and_then(|p| p.to_str());
+
+        self.patterns.iter().any(|pattern| {
+            name.is_some_and(|n| pattern.matches_str(n)) || relative.is_some_and(|r| pattern.matches_str(r))
+        })
+    }
+}
+
+/// 模式只编译一次；结尾的 `/` 只是标明目录，匹配时忽略
+fn compile(patterns: &[String]) -> Vec<NamePattern> {
+    patterns
+        .iter()
+        .map(|pattern| NamePattern::new(pattern.trim_end_matches('/'), false, None))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(patterns: &[&str]) -> ExcludeRules {
+        ExcludeRules {
+            root: PathBuf::from("/repo"),
+            patterns: compile(&patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>()),
+        }
+    }
+
+    #[test]
+    fn test_exclude_by_name() {
+        let rules = rules(&["target", "*.log"]);
+        assert!(rules.is_excluded_path(Path::new("/repo/target")));
+        assert!(rules.is_excluded_path(Path::new("/repo/apps/x/target")));
+        assert!(rules.is_excluded_path(Path::new("/repo/build.log")));
+        assert!(!rules.is_excluded_path(Path::new("/repo/src")));
+    }
+
+    #[test]
+    fn test_exclude_by_relative_path() {
+        let rules = rules(&["vendor/", "apps/*/target"]);
+        assert!(rules.is_excluded_path(Path::new("/repo/vendor")));
+        assert!(rules.is_excluded_path(Path::new("/repo/apps/find-rs/target")));
+        assert!(!rules.is_excluded_path(Path::new("/repo/target")));
+    }
+}
@@ -0,0 +1,295 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    actions::{
+        format::{type_char, type_name},
+        paths,
+    },
+    index::build::mode_of,
+    matcher::{fstype, mime},
+    types::{Cli, FileKind, FilterType, SizeSpec, TimeSpec},
+};
+
+/// 一个条件的判断结果
+#[derive(Debug)]
+pub struct Check {
+    pub predicate: &'static str,
+    pub passed: bool,
+    pub reason: String,
+}
+
+/// 逐个条件记录判断过程
+///
+/// 解释模式下所有条件都会被检查并记录原因；否则遇到第一个不满足的条件就停止，
+/// 原因字符串也不会生成。
+pub struct Evaluation<'a> {
+    checks: Option<&'a mut Vec<Check>>,
+    passed: bool,
+}
+
+impl<'a> Evaluation<'a> {
+    pub fn new(checks: Option<&'a mut Vec<Check>>) -> Self {
+        Evaluation { checks, passed: true }
+    }
+
+    /// 记录一个条件，返回是否需要继续检查后面的条件
+    pub fn record(&mut self, predicate: &'static str, passed: bool, reason: impl FnOnce() -> String) -> bool {
+        tracing::trace!(predicate, passed, "predicate evaluated");
+        self.passed &= passed;
+        match &mut self.checks {
+            Some(checks) => {
+                checks.push(Check { predicate, passed, reason: reason() });
+                true
+            }
+            None => passed,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.passed
+    }
+}
+
+/// `--explain`：把每个条目的判断过程输出到 stderr
+///
+/// 遍历器的剪枝和匹配阶段各持有一个克隆，共享同一个访问标记。
+#[derive(Clone, Debug)]
+pub struct Explainer {
+    /// 只解释这一个路径（绝对路径）
+    target: Option<PathBuf>,
+    /// 指定的路径是否被访问到
+    seen: Arc<AtomicBool>,
+}
+
+impl Explainer {
+    pub fn from_cli(cli: &Cli) -> Option<Explainer> {
+        let target = cli.explain.as_ref()?;
+        Some(Explainer {
+            target: target.as_deref().map(paths::absolute),
+            seen: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn wants(&self, path: &Path) -> bool {
+        let Some(target) = &self.target else {
+            return true;
+        };
+        // 先比较文件名，避免为每个条目规范化路径
+        if path.file_name() != target.file_name() || paths::absolute(path) != *target {
+            return false;
+        }
+        self.seen.store(true, Ordering::Relaxed);
+        true
+    }
+
+    pub fn report(&self, path: &Path, checks: &[Check], matched: bool) {
+        let verdict = if matched { "matched" } else { "rejected" };
+        if checks.is_empty() {
+            eprintln!("{}: {} (no criteria given)", path.display(), verdict);
+            return;
+        }
+        eprintln!("{}: {}", path.display(), verdict);
+        for check in checks {
+            let mark = if check.passed { "pass" } else { "FAIL" };
+            eprintln!("  [{}] {:<9} {}", mark, check.predicate, check.reason);
+        }
+    }
+
+    /// 遍历阶段被剪掉的条目（隐藏或排除），目录下的内容不会被访问
+    pub fn report_skipped(&self, path: &Path, is_dir: bool, reason: &str) {
+        if self.wants(path) {
+            let contents = if is_dir { ", contents not visited" } else { "" };
+            eprintln!("{}: skipped ({}){}", path.display(), reason, contents);
+        }
+    }
+
+    /// 搜索结束后，指定的路径从未被访问时给出提示
+    pub fn finish(&self) {
+        if let Some(target) = &self.target {
+            if !self.seen.load(Ordering::Relaxed) {
+                eprintln!(
+                    "{}: not visited (outside the search path, beyond the depth limits or inside a skipped directory)",
+                    target.display()
+                );
+            }
+        }
+    }
+}
+
+fn not(passed: bool) -> &'static str {
+    if passed { "" } else { "not " }
+}
+
+fn size_spec(spec: &SizeSpec) -> String {
+    match spec {
+        SizeSpec::GreaterThan(n) => format!("> {}", n),
+        SizeSpec::LessThan(n) => format!("< {}", n),
+        SizeSpec::Equal(n) => format!("== {}", n),
+    }
+}
+
+/// 以最大的两个单位显示时长，例如 `3d 4h`
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    let units = [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)];
+    let parts: Vec<String> = units
+        .iter()
+        .filter(|(_, unit)| secs >= *unit || *unit == 1)
+        .scan(secs, |rest, (name, unit)| {
+            let value = *rest / unit;
+            *rest %= unit;
+            Some((value, name))
+        })
+        .filter(|(value, _)| *value > 0)
+        .take(2)
+        .map(|(value, name)| format!("{}{}", value, name))
+        .collect();
+    if parts.is_empty() { "0s".to_string() } else { parts.join(" ") }
+}
+
+pub fn file_type(path: &Path, expected: FilterType, passed: bool) -> String {
+    let actual = match fs::symlink_metadata(path) {
+        Ok(metadata) => type_name(type_char(&metadata)),
+        Err(_) => "unreadable",
+    };
+    format!("type {} is {}{:?}", actual, not(passed), expected).to_lowercase()
+}
+
+pub fn name(path: &Path, pattern: &str, case_insensitive: bool, passed: bool) -> String {
+    let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let case = if case_insensitive { " (ignoring case)" } else { "" };
+    let verb = if passed { "matches" } else { "does not match" };
+    format!("name '{}' {} '{}'{}", file_name, verb, pattern, case)
+}
+
+pub fn regex(path: &Path, pattern: &str, case_insensitive: bool, passed: bool) -> String {
+    let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let case = if case_insensitive { " (ignoring case)" } else { "" };
+    let verb = if passed { "matches" } else { "does not match" };
+    format!("name '{}' {} /{}/{}", file_name, verb, pattern, case)
+}
+
+pub fn size(path: &Path, spec: &SizeSpec, passed: bool) -> String {
+    match fs::metadata(path) {
+        Ok(metadata) => format!("size {} {}{}", metadata.len(), not(passed), size_spec(spec)),
+        Err(e) => format!("size unavailable: {}", e),
+    }
+}
+
+pub fn mtime(path: &Path, spec: &TimeSpec, passed: bool) -> String {
+    let age = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| SystemTime::now().duration_since(modified).unwrap_or_default());
+    let Ok(age) = age else {
+        return "modification time unavailable".to_string();
+    };
+    let expected = match spec {
+        TimeSpec::NewerThan(limit) => format!("< {}", format_age(*limit)),
+        TimeSpec::OlderThan(limit) => format!("> {}", format_age(*limit)),
+        TimeSpec::Equal(limit) => format!("== {}", format_age(*limit)),
+    };
+    format!("age {} {}{}", format_age(age), not(passed), expected)
+}
+
+pub fn links(path: &Path, spec: &SizeSpec, passed: bool) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        if let Ok(metadata) = fs::symlink_metadata(path) {
+            return format!("link count {} {}{}", metadata.nlink(), not(passed), size_spec(spec));
+        }
+    }
+    format!("link count unavailable, {}{}", not(passed), size_spec(spec))
+}
+
+pub fn inode(path: &Path, expected: u64, passed: bool) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        if let Ok(metadata) = fs::symlink_metadata(path) {
+            let op = if passed { "==" } else { "!=" };
+            return format!("inode {} {} {}", metadata.ino(), op, expected);
+        }
+    }
+    format!("inode unavailable, expected {}", expected)
+}
+
+pub fn mode(path: &Path, passed: bool) -> String {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) => {
+            let verb = if passed { "satisfies" } else { "does not satisfy" };
+            format!("mode {:04o} {} --perm", mode_of(&metadata), verb)
+        }
+        Err(e) => format!("mode unavailable: {}", e),
+    }
+}
+
+pub fn fstype(path: &Path, expected: &str, passed: bool) -> String {
+    let actual = fstype::filesystem_type(path).unwrap_or_else(|| "unknown".to_string());
+    let op = if passed { "==" } else { "!=" };
+    format!("filesystem {} {} {}", actual, op, expected)
+}
+
+pub fn mime_type(path: &Path, pattern: &str, passed: bool) -> String {
+    let actual = mime::sniff(path).map_or("unknown", |sniffed| sniffed.mime);
+    let verb = if passed { "matches" } else { "does not match" };
+    format!("content type {} {} '{}'", actual, verb, pattern)
+}
+
+pub fn kind(path: &Path, expected: FileKind, passed: bool) -> String {
+    let actual = mime::sniff(path).map_or("unknown", |sniffed| sniffed.mime);
+    format!("content type {} is {}{:?}", actual, not(passed), expected)
+}
+
+pub fn flag(what: &str, passed: bool) -> String {
+    format!("{}{}", not(passed), what)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluation_short_circuits_without_explain() {
+        let mut evaluation = Evaluation::new(None);
+        assert!(!evaluation.record("size", false, || unreachable!()));
+        assert!(!evaluation.passed());
+
+        let mut checks = Vec::new();
+        let mut evaluation = Evaluation::new(Some(&mut checks));
+        assert!(evaluation.record("size", false, || "size 1200 not > 2048".to_string()));
+        assert!(evaluation.record("type", true, || "type file is File".to_string()));
+        assert!(!evaluation.passed());
+        assert_eq!(checks.len(), 2);
+    }
+
+    #[test]
+    fn test_clones_share_visited_flag() {
+        let cli = <Cli as clap::Parser>::try_parse_from(["find-rs", ".", "--explain=notes.txt"]).unwrap();
+        let explainer = Explainer::from_cli(&cli).unwrap();
+        let pruning = explainer.clone();
+        assert!(!pruning.wants(Path::new("./src")));
+        assert!(pruning.wants(Path::new("./notes.txt")));
+        assert!(explainer.seen.load(Ordering::Relaxed));
+
+        // 每次搜索都从未访问开始
+        assert!(!Explainer::from_cli(&cli).unwrap().seen.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(0)), "0s");
+        assert_eq!(format_age(Duration::from_secs(90)), "1m 30s");
+        assert_eq!(format_age(Duration::from_secs(3 * 86400 + 4 * 3600 + 5)), "3d 4h");
+    }
+}
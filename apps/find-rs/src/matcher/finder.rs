@@ -1,130 +1,459 @@
-use std::{path::{Path, PathBuf}};
+use std::{collections::HashSet, fs, path::{Path, PathBuf}, time::SystemTime};
 
-use walkdir::{DirEntry, WalkDir};
+use crate::{index::Index, matcher::{archive, empty::match_empty, exclude::ExcludeRules, explain::{self, Check, Evaluation, Explainer}, filter_type, fstype::match_fstype, git::{self, GitStatus}, hidden::HiddenRules, inode, mime, mtime::match_mtime, name::{self, NamePattern}, perm::match_perm, regex, size::match_size, walker::get_walker}, types::{ArchiveMember, Cli, FilterType, FindError, FindResult, FoundFile, Normalization}};
 
-use crate::{matcher, matcher::{filter_type, mtime::match_mtime, size::match_size}, types::{Cli, FindResult}};
+pub fn find_files(cli: &Cli) -> FindResult<Vec<FoundFile>> {
+    let mut matches = Vec::new();
+    find_each(cli, |file| {
+        matches.push(file);
+        true
+    })?;
+    Ok(matches)
+}
 
-pub fn find_files(walker: WalkDir, cli: &Cli) -> FindResult<Vec<PathBuf>> {
-    let matches = walker
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|entry| filter_hidden(entry, cli))
-        .map(|entry| entry.path().to_path_buf())
-        .filter(|path| matches_criteria(path, cli))
-        .collect();
+/// 按命令行选择索引或遍历文件系统，逐个报告匹配项
+pub fn search_each(cli: &Cli, mut on_match: impl FnMut(FoundFile) -> bool) -> FindResult<()> {
+    if cli.criteria.from_index {
+        let index = crate::index::load_for(&cli.path, cli.criteria.index_file.as_deref())?;
+        for file in find_in_index(&index, cli)? {
+            if !on_match(file) {
+                break;
+            }
+        }
+        Ok(())
+    } else {
+        find_each(cli, on_match)
+    }
+}
 
-    Ok(matches)
+/// 遍历文件系统，逐个报告匹配项，回调返回 false 时停止搜索（例如交互界面已经退出）
+pub fn find_each(cli: &Cli, mut on_match: impl FnMut(FoundFile) -> bool) -> FindResult<()> {
+    let mut ctx = MatchContext::new(cli)?;
+    // 遍历器剪枝时也要报告被跳过的条目，两者共享同一个解释器
+    let explainer = Explainer::from_cli(cli);
+    let mut walker = get_walker(cli, explainer.as_ref());
+    // 后序遍历并删除时，记录即将被删除的条目，供 --empty 判断父目录
+    let track_pending = cli.criteria.post_order && cli.actions.delete;
+
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+
+        let _span = tracing::debug_span!("entry", path = %entry.path().display()).entered();
+        let matched = match &explainer {
+            Some(explainer) if explainer.wants(entry.path()) => {
+                let mut checks = Vec::new();
+                let matched = evaluate_criteria(entry.path(), cli, &ctx, Some(&mut checks));
+                explainer.report(entry.path(), &checks, matched);
+                matched
+            }
+            _ => matches_criteria(entry.path(), cli, &ctx),
+        };
+        tracing::debug!(matched, "entry evaluated");
+
+        if matched {
+            if track_pending {
+                ctx.pending.insert(entry.path().to_path_buf());
+            }
+
+            // --prune：匹配到的目录本身会输出，但不再进入其内部
+            if cli.criteria.prune && entry.file_type().is_dir() {
+                walker.skip_current_dir();
+            }
+
+            let found = FoundFile {
+                depth: entry.depth(),
+                path: entry.path().to_path_buf(),
+                member: None,
+            };
+            if !on_match(found) {
+                break;
+            }
+        }
+
+        if cli.criteria.search_archives
+            && entry.file_type().is_file()
+            && archive::is_archive(entry.path())
+            && !search_archive(entry.path(), entry.depth(), cli, &ctx, &mut on_match)
+        {
+            break;
+        }
+    }
+
+    if let Some(explainer) = &explainer {
+        explainer.finish();
+    }
+    Ok(())
+}
+
+/// 把压缩包成员当作虚拟条目进行匹配，回调要求停止时返回 false
+fn search_archive(
+    path: &Path,
+    depth: usize,
+    cli: &Cli,
+    ctx: &MatchContext,
+    on_match: &mut impl FnMut(FoundFile) -> bool,
+) -> bool {
+    let members = match archive::read_members(path) {
+        Ok(members) => members,
+        Err(e) => {
+            eprintln!("Warning: Failed to read archive {}: {}", path.display(), e);
+            return true;
+        }
+    };
+
+    for member in members {
+        let member_depth = depth + member.name.components().count();
+        if cli.criteria.depth.is_some_and(|max| member_depth > max)
+            || cli.criteria.min_depth.is_some_and(|min| member_depth < min)
+        {
+            continue;
+        }
+
+        let virtual_path = member.virtual_path();
+        if ctx.skips_member(&member) || !matches_member(&virtual_path, &member, cli, ctx) {
+            continue;
+        }
+
+        let found = FoundFile {
+            path: virtual_path,
+            depth: member_depth,
+            member: Some(member),
+        };
+        if !on_match(found) {
+            return false;
+        }
+    }
+
+    true
 }
 
-fn filter_hidden(entry: &DirEntry, cli: &Cli) -> bool {
-    if cli.criteria.all {
-        return true;
+/// 压缩包成员只能使用不依赖真实文件的条件
+fn matches_member(virtual_path: &Path, member: &ArchiveMember, cli: &Cli, ctx: &MatchContext) -> bool {
+    let criteria = &cli.criteria;
+
+    // 依赖 inode、挂载点、文件内容或 git 状态的条件无法用于压缩包成员
+    if criteria.inum.is_some()
+        || criteria.links.is_some()
+        || criteria.samefile.is_some()
+        || criteria.fstype.is_some()
+        || criteria.mime.is_some()
+        || criteria.kind.is_some()
+        || ctx.git.is_some()
+    {
+        return false;
     }
-    
-    // 检查是否是隐藏文件/目录
-    if let Some(file_name) = entry.file_name().to_str() {
-        // 在Unix系统上，以点开头的文件是隐藏文件
-        if file_name.starts_with('.') {
+
+    let record = Recorded {
+        kind: member.kind,
+        size: member.size,
+        modified: member.modified,
+        mode: member.mode,
+    };
+    matches_recorded(virtual_path, &record, cli, ctx)
+}
+
+/// 不访问文件系统时可用的元数据，来自压缩包成员或索引条目
+struct Recorded {
+    kind: FilterType,
+    size: u64,
+    modified: SystemTime,
+    mode: u32,
+}
+
+fn matches_recorded(path: &Path, record: &Recorded, cli: &Cli, ctx: &MatchContext) -> bool {
+    let criteria = &cli.criteria;
+
+    if criteria.filter_type.is_some_and(|filter_type| filter_type != record.kind) {
+        return false;
+    }
+
+    if let Some(pattern) = &ctx.name {
+        if !matches_name(path, pattern) {
             return false;
         }
-        
-        // 检查文件所在的目录是否有隐藏的父目录
-        // 注意：这里我们只检查当前条目本身，不递归检查父目录
-        // 如果需要，可以添加递归检查
-        true
-    } else {
-        // 如果文件名不是有效的UTF-8，我们不过滤它
-        true
     }
+
+    if let Some(regex) = &criteria.regex {
+        if !matches_regex(path, regex, ctx.regex_insensitive, criteria.normalize) {
+            return false;
+        }
+    }
+
+    if let Some(size_spec) = &criteria.size {
+        if !size_spec.matches(record.size) {
+            return false;
+        }
+    }
+
+    if let Some(time_spec) = &criteria.mtime {
+        match SystemTime::now().duration_since(record.modified) {
+            Ok(age) if time_spec.matches(age) => {}
+            _ => return false,
+        }
+    }
+
+    if criteria.empty && !(record.kind == FilterType::File && record.size == 0) {
+        return false;
+    }
+
+    if criteria.perm.is_some_and(|perm| !perm.matches(record.mode)) {
+        return false;
+    }
+
+    true
 }
 
-// 优化的匹配函数
-fn matches_criteria(path: &Path, cli: &Cli) -> bool {
-    // 调试：打印正在检查的路径
-    if std::env::var("FIND_RS_DEBUG").is_ok() {
-        eprintln!("Debug: Checking path: {}", path.display());
+/// 从持久化索引中查找，只使用索引记录的元数据，不访问文件系统
+pub fn find_in_index(index: &Index, cli: &Cli) -> FindResult<Vec<FoundFile>> {
+    let criteria = &cli.criteria;
+    let unsupported = [
+        ("--mime", criteria.mime.is_some()),
+        ("--kind", criteria.kind.is_some()),
+        ("--empty", criteria.empty),
+        ("--inum", criteria.inum.is_some()),
+        ("--links", criteria.links.is_some()),
+        ("--samefile", criteria.samefile.is_some()),
+        ("--fstype", criteria.fstype.is_some()),
+        ("--post-order", criteria.post_order),
+        ("--search-archives", criteria.search_archives),
+        ("git filters", git::has_git_criteria(criteria)),
+    ];
+    if let Some((flag, _)) = unsupported.iter().find(|(_, used)| *used) {
+        return Err(FindError::Index(format!("{} cannot be used with --from-index", flag)));
     }
-    
-    // 先检查文件类型（如果指定了的话）
-    if let Some(filter_type) = &cli.criteria.filter_type {
-        if !filter_type::match_file_type(path, *filter_type) {
-            if std::env::var("FIND_RS_DEBUG").is_ok() {
-                eprintln!("Debug: Failed file type filter");
+
+    let ctx = MatchContext::new(cli)?;
+    let search_root = fs::canonicalize(&cli.path)?;
+    let mut matches = Vec::new();
+    // 被隐藏、排除或剪枝的目录，其下的条目全部跳过
+    let mut skipped: Option<PathBuf> = None;
+
+    for entry in &index.entries {
+        let absolute = index.root.join(&entry.path);
+        let Ok(relative) = absolute.strip_prefix(&search_root) else {
+            continue;
+        };
+        if skipped.as_deref().is_some_and(|dir| relative.starts_with(dir)) {
+            continue;
+        }
+
+        // 输出时保留用户给出的搜索路径形式，与遍历时一致
+        let depth = relative.components().count();
+        let path = if depth == 0 { cli.path.clone() } else { cli.path.join(relative) };
+
+        let hidden = relative.file_name().is_some_and(|name| ctx.hidden.is_hidden_name(name));
+        if depth > 0 && (hidden || ctx.exclude.is_excluded_path(&path)) {
+            skipped = Some(relative.to_path_buf());
+            continue;
+        }
+
+        if criteria.depth.is_some_and(|max| depth > max) || criteria.min_depth.is_some_and(|min| depth < min) {
+            continue;
+        }
+
+        let record = Recorded {
+            kind: entry.kind,
+            size: entry.size,
+            modified: entry.modified,
+            mode: entry.mode,
+        };
+        if matches_recorded(&path, &record, cli, &ctx) {
+            if criteria.prune && entry.kind == FilterType::Directory {
+                skipped = Some(relative.to_path_buf());
             }
+            matches.push(FoundFile { path, depth, member: None });
+        }
+    }
+
+    Ok(matches)
+}
+
+/// 遍历期间复用的匹配状态，模式只编译一次
+struct MatchContext {
+    name: Option<NamePattern>,
+    mime: Option<NamePattern>,
+    regex_insensitive: bool,
+    pending: HashSet<PathBuf>,
+    // 压缩包内部没有遍历器剪枝，需要手动应用隐藏与排除规则
+    hidden: HiddenRules,
+    exclude: ExcludeRules,
+    git: Option<GitStatus>,
+}
+
+impl MatchContext {
+    fn new(cli: &Cli) -> FindResult<Self> {
+        let criteria = &cli.criteria;
+        let git = if git::has_git_criteria(criteria) {
+            Some(GitStatus::load(&cli.path, criteria)?)
+        } else {
+            None
+        };
+
+        Ok(MatchContext {
+            name: criteria.name.as_deref().map(|pattern| {
+                NamePattern::new(pattern, criteria.ignore_case(pattern, false), criteria.normalize)
+            }),
+            mime: criteria.mime.as_deref().map(|pattern| NamePattern::new(pattern, true, None)),
+            regex_insensitive: criteria
+                .regex
+                .as_deref()
+                .is_some_and(|pattern| criteria.ignore_case(pattern, true)),
+            pending: HashSet::new(),
+            hidden: HiddenRules::from_criteria(criteria),
+            exclude: ExcludeRules::from_cli(cli),
+            git,
+        })
+    }
+
+    /// 成员路径上任意一级被隐藏或排除，成员就被跳过
+    fn skips_member(&self, member: &ArchiveMember) -> bool {
+        let mut prefix = PathBuf::new();
+        member.name.components().any(|component| {
+            prefix.push(component);
+            let virtual_path = ArchiveMember::join_virtual(&member.archive, &prefix);
+            self.hidden.is_hidden_name(component.as_os_str()) || self.exclude.is_excluded_path(&virtual_path)
+        })
+    }
+}
+
+fn matches_criteria(path: &Path, cli: &Cli, ctx: &MatchContext) -> bool {
+    evaluate_criteria(path, cli, ctx, None)
+}
+
+/// 依次检查各个条件；给出 `checks` 时检查全部条件并记录原因，供 `--explain` 输出
+fn evaluate_criteria(path: &Path, cli: &Cli, ctx: &MatchContext, checks: Option<&mut Vec<Check>>) -> bool {
+    let criteria = &cli.criteria;
+    let mut eval = Evaluation::new(checks);
+
+    // 先检查文件类型（如果指定了的话）
+    if let Some(filter_type) = criteria.filter_type {
+        let passed = filter_type::match_file_type(path, filter_type);
+        if !eval.record("type", passed, || explain::file_type(path, filter_type, passed)) {
             return false;
         }
     }
-    
+
     // 然后检查名称匹配（如果指定了的话）
-    if let Some(name) = &cli.criteria.name {
-        if !matches_name(path, name, cli.criteria.insensitive) {
-            if std::env::var("FIND_RS_DEBUG").is_ok() {
-                eprintln!("Debug: Failed name filter: pattern={}, path={}", name, path.display());
-            }
+    if let (Some(name), Some(pattern)) = (&criteria.name, &ctx.name) {
+        let passed = matches_name(path, pattern);
+        let reason = || explain::name(path, name, criteria.ignore_case(name, false), passed);
+        if !eval.record("name", passed, reason) {
             return false;
         }
     }
-    
+
     // 检查正则表达式匹配
-    if let Some(regex) = &cli.criteria.regex {
-        if !matches_regex(path, regex, cli.criteria.insensitive) {
+    if let Some(pattern) = &criteria.regex {
+        let passed = matches_regex(path, pattern, ctx.regex_insensitive, criteria.normalize);
+        if !eval.record("regex", passed, || explain::regex(path, pattern, ctx.regex_insensitive, passed)) {
             return false;
         }
     }
-    
+
     // 最后检查需要文件系统操作的条件
-    if let Some(size_spec) = &cli.criteria.size {
-        if !match_size(path, size_spec) {
+    if let Some(size_spec) = &criteria.size {
+        let passed = match_size(path, size_spec);
+        if !eval.record("size", passed, || explain::size(path, size_spec, passed)) {
             return false;
         }
     }
-    
-    if let Some(time_spec) = &cli.criteria.mtime {
-        if !match_mtime(path, time_spec) {
+
+    if let Some(time_spec) = &criteria.mtime {
+        let passed = match_mtime(path, time_spec);
+        if !eval.record("mtime", passed, || explain::mtime(path, time_spec, passed)) {
             return false;
         }
     }
-    
-    true
+
+    if criteria.empty {
+        let passed = match_empty(path, &ctx.pending);
+        if !eval.record("empty", passed, || explain::flag("empty", passed)) {
+            return false;
+        }
+    }
+
+    if let Some(inum) = criteria.inum {
+        let passed = inode::match_inode(path, inum);
+        if !eval.record("inum", passed, || explain::inode(path, inum, passed)) {
+            return false;
+        }
+    }
+
+    if let Some(link_spec) = &criteria.links {
+        let passed = inode::match_links(path, link_spec);
+        if !eval.record("links", passed, || explain::links(path, link_spec, passed)) {
+            return false;
+        }
+    }
+
+    if let Some(file_id) = &criteria.samefile {
+        let passed = inode::match_samefile(path, file_id);
+        if !eval.record("samefile", passed, || explain::flag("the same file as --samefile", passed)) {
+            return false;
+        }
+    }
+
+    if let Some(perm) = &criteria.perm {
+        let passed = match_perm(path, perm);
+        if !eval.record("perm", passed, || explain::mode(path, passed)) {
+            return false;
+        }
+    }
+
+    if let Some(fstype) = &criteria.fstype {
+        let passed = match_fstype(path, fstype);
+        if !eval.record("fstype", passed, || explain::fstype(path, fstype, passed)) {
+            return false;
+        }
+    }
+
+    if let Some(git) = &ctx.git {
+        let passed = git.matches(path, criteria);
+        if !eval.record("git", passed, || explain::flag("in the requested git state", passed)) {
+            return false;
+        }
+    }
+
+    // 内容嗅探需要读取文件，放在其他条件之后
+    if let (Some(pattern), Some(compiled)) = (&criteria.mime, &ctx.mime) {
+        let passed = mime::match_mime(path, compiled);
+        if !eval.record("mime", passed, || explain::mime_type(path, pattern, passed)) {
+            return false;
+        }
+    }
+
+    if let Some(kind) = criteria.kind {
+        let passed = mime::match_kind(path, kind);
+        if !eval.record("kind", passed, || explain::kind(path, kind, passed)) {
+            return false;
+        }
+    }
+
+    eval.passed()
 }
 
-fn matches_name(path: &Path, pattern: &str, insensitive: bool) -> bool {
+fn matches_name(path: &Path, pattern: &NamePattern) -> bool {
     // 首先检查路径是否有文件名
     let Some(file_name) = path.file_name() else {
         return false;
     };
     
-    // 尝试转换为字符串
-    let Some(file_name_str) = file_name.to_str() else {
-        // 如果文件名不是有效的UTF-8，使用原始字节进行比较
-        return matches_name_bytes(file_name, pattern, insensitive);
-    };
-    
-    use crate::matcher::name::match_name;
-    match_name(file_name_str, pattern, insensitive)
+    // 非 UTF-8 文件名同样按原始字节匹配
+    pattern.matches_bytes(file_name.as_encoded_bytes())
 }
 
-/// 处理非UTF-8文件名的匹配
-fn matches_name_bytes(file_name: &std::ffi::OsStr, pattern: &str, insensitive: bool) -> bool {
-    // 如果大小写敏感，直接比较字节
-    if !insensitive {
-        return file_name == pattern;
-    }
-    
-    // 对于大小写不敏感，我们进行简单处理
-    // 注意：这是一个简化的实现，不处理所有Unicode情况
-    let pattern_lower = pattern.to_lowercase();
-    if let Some(file_name_str) = file_name.to_str() {
-        file_name_str.to_lowercase().contains(&pattern_lower)
-    } else {
-        false
-    }
-}
+fn matches_regex(path: &Path, pattern: &str, insensitive: bool, normalization: Option<Normalization>) -> bool {
+    let Some(file_name) = path.file_name() else {
+        return false;
+    };
 
-fn matches_regex(path: &Path, pattern: &str, insensitive: bool) -> bool {
-    if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-        matcher::regex::regex_match(file_name, pattern, insensitive)
-    } else {
-        false
+    match file_name.to_str() {
+        Some(file_name) => {
+            let file_name = name::normalize(file_name, normalization);
+            regex::regex_match(&file_name, pattern, insensitive)
+        }
+        None => regex::regex_match_bytes(file_name.as_encoded_bytes(), pattern, insensitive),
     }
 }
@@ -0,0 +1,68 @@
+use std::{collections::HashMap, path::Path};
+
+use once_cell::sync::Lazy;
+
+// 设备号 (major, minor) 到文件系统类型的映射，来自 /proc/self/mountinfo
+static MOUNTS: Lazy<HashMap<(u32, u32), String>> = Lazy::new(|| {
+    std::fs::read_to_string("/proc/self/mountinfo")
+        .map(|content| parse_mountinfo(&content))
+        .unwrap_or_default()
+});
+
+/// 解析 mountinfo，格式参见 proc(5)：
+/// `36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw`
+fn parse_mountinfo(content: &str) -> HashMap<(u32, u32), String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (major, minor) = fields.get(2)?.split_once(':')?;
+            // 可选字段数量不固定，文件系统类型位于分隔符 "-" 之后
+            let separator = fields.iter().position(|f| *f == "-")?;
+            let fstype = fields.get(separator + 1)?;
+            Some(((major.parse().ok()?, minor.parse().ok()?), fstype.to_string()))
+        })
+        .collect()
+}
+
+/// 获取路径所在文件系统的类型
+pub fn filesystem_type(path: &Path) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let dev = std::fs::symlink_metadata(path).ok()?.dev();
+        // glibc 的 major()/minor() 编码方式
+        let major = (((dev >> 32) & 0xffff_f000) | ((dev >> 8) & 0x0000_0fff)) as u32;
+        let minor = (((dev >> 12) & 0xffff_ff00) | (dev & 0x0000_00ff)) as u32;
+        MOUNTS.get(&(major, minor)).cloned()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+pub fn match_fstype(path: &Path, fstype: &str) -> bool {
+    filesystem_type(path).is_some_and(|t| t == fstype)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let content = "\
+22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw
+23 22 0:21 / /proc rw,nosuid shared:12 - proc proc rw
+24 22 0:22 / /tmp rw - tmpfs tmpfs rw,size=1024k
+broken line";
+        let mounts = parse_mountinfo(content);
+        assert_eq!(mounts.len(), 3);
+        assert_eq!(mounts[&(8, 1)], "ext4");
+        assert_eq!(mounts[&(0, 21)], "proc");
+        assert_eq!(mounts[&(0, 22)], "tmpfs");
+    }
+}
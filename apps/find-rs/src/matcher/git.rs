@@ -0,0 +1,208 @@
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::types::{FindError, FindResult, SearchCriteria};
+
+/// 工作区中各类文件的状态，路径均相对于仓库根目录
+#[derive(Debug, Default)]
+pub struct GitStatus {
+    toplevel: PathBuf,
+    modified: HashSet<PathBuf>,
+    untracked: HashSet<PathBuf>,
+    ignored: HashSet<PathBuf>,
+    changed_since: Option<HashSet<PathBuf>>,
+}
+
+impl GitStatus {
+    /// 在搜索起点所在的仓库中收集状态，只有指定了 git 相关条件时才会调用
+    pub fn load(search_path: &Path, criteria: &SearchCriteria) -> FindResult<Self> {
+        let dir = if search_path.is_dir() {
+            search_path
+        } else {
+            search_path.parent().unwrap_or(Path::new("."))
+        };
+
+        let mut toplevel = run_git(dir, &["rev-parse", "--show-toplevel"])?;
+        if toplevel.last() == Some(&b'\n') {
+            toplevel.pop();
+        }
+        let toplevel = fs::canonicalize(path_from_bytes(&toplevel))?;
+
+        let status = run_git(
+            &toplevel,
+            &["status", "--porcelain=v1", "-z", "--ignored=matching", "--untracked-files=normal"],
+        )?;
+        let mut git_status = parse_porcelain(&status);
+        git_status.toplevel = toplevel;
+
+        if let Some(rev) = &criteria.git_changed_since {
+            run_git(&git_status.toplevel, &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", rev)])
+                .map_err(|_| FindError::Git(format!("unknown revision '{}'", rev)))?;
+
+            let diff = run_git(&git_status.toplevel, &["diff", "--name-only", "-z", rev, "--"])?;
+            git_status.changed_since = Some(
+                diff.split(|b| *b == 0)
+                    .filter(|p| !p.is_empty())
+                    .map(path_from_bytes)
+                    .collect(),
+            );
+        }
+
+        Ok(git_status)
+    }
+
+    /// 判断路径是否满足所有指定的 git 条件
+    pub fn matches(&self, path: &Path, criteria: &SearchCriteria) -> bool {
+        let Some(relative) = self.repo_relative(path) else {
+            return false;
+        };
+
+        if criteria.git_modified && !self.modified.contains(&relative) {
+            return false;
+        }
+
+        // 未跟踪或忽略的目录只会报告目录本身，其下的内容继承同样的状态
+        if criteria.git_untracked && !contains_self_or_ancestor(&self.untracked, &relative) {
+            return false;
+        }
+
+        if criteria.git_ignored && !contains_self_or_ancestor(&self.ignored, &relative) {
+            return false;
+        }
+
+        if let Some(changed) = &self.changed_since {
+            if !changed.contains(&relative) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn repo_relative(&self, path: &Path) -> Option<PathBuf> {
+        // 只规范化父目录，避免把被跟踪的符号链接解析成目标文件
+        let absolute = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => {
+                let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+                fs::canonicalize(parent).ok()?.join(name)
+            }
+            _ => fs::canonicalize(path).ok()?,
+        };
+        absolute.strip_prefix(&self.toplevel).ok().map(Path::to_path_buf)
+    }
+}
+
+pub fn has_git_criteria(criteria: &SearchCriteria) -> bool {
+    criteria.git_modified
+        || criteria.git_untracked
+        || criteria.git_ignored
+        || criteria.git_changed_since.is_some()
+}
+
+fn contains_self_or_ancestor(set: &HashSet<PathBuf>, path: &Path) -> bool {
+    path.ancestors()
+        .filter(|p| !p.as_os_str().is_empty())
+        .any(|p| set.contains(p))
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> FindResult<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => {
+                FindError::Git("git executable not found in PATH (required by the --git-* filters)".to_string())
+            }
+            _ => FindError::Git(format!("failed to run git: {}", e)),
+        })?;
+
+    if !output.status.success() {
+        return Err(FindError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+/// git 输出的路径是原始字节，非 UTF-8 的文件名不能经过有损转换
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+
+    PathBuf::from(OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// 解析 `git status --porcelain=v1 -z` 的输出
+///
+/// 每条记录为 `XY path\0`，重命名和复制会在后面多带一个原路径 `\0old\0`。
+fn parse_porcelain(output: &[u8]) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut records = output.split(|b| *b == 0).filter(|r| !r.is_empty());
+
+    while let Some(record) = records.next() {
+        let Some((code, path)) = record.split_at_checked(3) else {
+            continue;
+        };
+        let path = path_from_bytes(path.strip_suffix(b"/").unwrap_or(path));
+
+        match code.trim_ascii_end() {
+            b"??" => {
+                status.untracked.insert(path);
+            }
+            b"!!" => {
+                status.ignored.insert(path);
+            }
+            code => {
+                if code.starts_with(b"R") || code.starts_with(b"C") {
+                    // 跳过原路径
+                    records.next();
+                }
+                status.modified.insert(path);
+            }
+        }
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_porcelain() {
+        let output = b" M src/main.rs\0M  src/lib.rs\0R  new.rs\0old.rs\0?? notes/\0?? todo.txt\0!! target/\0";
+        let status = parse_porcelain(output);
+
+        assert!(status.modified.contains(Path::new("src/main.rs")));
+        assert!(status.modified.contains(Path::new("src/lib.rs")));
+        assert!(status.modified.contains(Path::new("new.rs")));
+        assert!(!status.modified.contains(Path::new("old.rs")));
+        assert!(status.untracked.contains(Path::new("todo.txt")));
+        assert!(contains_self_or_ancestor(&status.untracked, Path::new("notes/a/b.md")));
+        assert!(contains_self_or_ancestor(&status.ignored, Path::new("target/debug/find-rs")));
+        assert!(!contains_self_or_ancestor(&status.ignored, Path::new("src/main.rs")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_paths_are_kept() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let status = parse_porcelain(b" M caf\xe9.txt\0");
+        assert!(status.modified.contains(Path::new(OsStr::from_bytes(b"caf\xe9.txt"))));
+    }
+}
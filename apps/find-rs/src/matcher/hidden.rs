@@ -0,0 +1,85 @@
+use std::ffi::OsStr;
+
+use walkdir::DirEntry;
+
+use crate::{matcher::name::NamePattern, types::SearchCriteria};
+
+/// 隐藏文件判定规则
+///
+/// 默认规则是以点开头的文件，另外可以通过 `--hidden-pattern` 追加通配符规则。
+#[derive(Clone, Debug, Default)]
+pub struct HiddenRules {
+    dotfiles: bool,
+    patterns: Vec<NamePattern>,
+}
+
+impl HiddenRules {
+    pub fn from_criteria(criteria: &SearchCriteria) -> Self {
+        if criteria.all {
+            return HiddenRules::default();
+        }
+
+        HiddenRules {
+            dotfiles: true,
+            patterns: criteria
+                .hidden_patterns
+                .iter()
+                .map(|pattern| NamePattern::new(pattern, false, None))
+                .collect(),
+        }
+    }
+
+    /// 判断遍历到的条目是否隐藏，搜索起点本身永远不算隐藏
+    pub fn is_hidden(&self, entry: &DirEntry) -> bool {
+        entry.depth() > 0 && self.is_hidden_name(entry.file_name())
+    }
+
+    pub fn is_hidden_name(&self, file_name: &OsStr) -> bool {
+        // 直接检查原始字节，非 UTF-8 文件名同样适用
+        if self.dotfiles && file_name.as_encoded_bytes().starts_with(b".") {
+            return true;
+        }
+
+        let Some(name) = file_name.to_str() else {
+            return false;
+        };
+        self.patterns.iter().any(|pattern| pattern.matches_str(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(dotfiles: bool, patterns: &[&str]) -> HiddenRules {
+        HiddenRules {
+            dotfiles,
+            patterns: patterns.iter().map(|p| NamePattern::new(p, false, None)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_dotfiles() {
+        let rules = rules(true, &[]);
+        assert!(rules.is_hidden_name(OsStr::new(".git")));
+        assert!(!rules.is_hidden_name(OsStr::new("src")));
+    }
+
+    #[test]
+    fn test_user_patterns() {
+        let rules = rules(false, &["*~", "__pycache__"]);
+        assert!(rules.is_hidden_name(OsStr::new("main.rs~")));
+        assert!(rules.is_hidden_name(OsStr::new("__pycache__")));
+        assert!(!rules.is_hidden_name(OsStr::new(".env")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_dotfile() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let rules = rules(true, &[]);
+        assert!(rules.is_hidden_name(OsStr::from_bytes(b".\xff\xfe")));
+        assert!(!rules.is_hidden_name(OsStr::from_bytes(b"\xff\xfe")));
+    }
+}
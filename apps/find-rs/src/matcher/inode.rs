@@ -0,0 +1,78 @@
+use std::{fs::symlink_metadata, path::Path};
+
+use crate::types::{FileId, SizeSpec};
+
+#[cfg(unix)]
+pub fn match_inode(path: &Path, inode: u64) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    symlink_metadata(path).is_ok_and(|m| m.ino() == inode)
+}
+
+#[cfg(unix)]
+pub fn match_links(path: &Path, link_spec: &SizeSpec) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    symlink_metadata(path).is_ok_and(|m| link_spec.matches(m.nlink()))
+}
+
+#[cfg(unix)]
+pub fn match_samefile(path: &Path, file_id: &FileId) -> bool {
+    FileId::from_path(path).is_ok_and(|id| id == *file_id)
+}
+
+#[cfg(not(unix))]
+pub fn match_inode(_path: &Path, _inode: u64) -> bool {
+    false
+}
+
+#[cfg(not(unix))]
+pub fn match_links(_path: &Path, _link_spec: &SizeSpec) -> bool {
+    false
+}
+
+#[cfg(not(unix))]
+pub fn match_samefile(_path: &Path, _file_id: &FileId) -> bool {
+    false
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::MetadataExt;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hardlink_predicates() {
+        let dir = TempDir::new().unwrap();
+        let original = dir.path().join("original");
+        let link = dir.path().join("link");
+        let other = dir.path().join("other");
+        fs::write(&original, "data").unwrap();
+        fs::write(&other, "data").unwrap();
+        fs::hard_link(&original, &link).unwrap();
+
+        let id = FileId::from_path(&original).unwrap();
+        assert!(match_samefile(&link, &id));
+        assert!(!match_samefile(&other, &id));
+
+        let inode = fs::metadata(&original).unwrap().ino();
+        assert!(match_inode(&link, inode));
+
+        assert!(match_links(&link, &SizeSpec::Equal(2)));
+        assert!(match_links(&link, &SizeSpec::GreaterThan(1)));
+        assert!(match_links(&other, &SizeSpec::LessThan(2)));
+    }
+
+    #[test]
+    fn test_links_accepts_leading_minus() {
+        use clap::Parser;
+        use crate::types::Cli;
+
+        let cli = Cli::try_parse_from(["find-rs", ".", "--links", "-3"]).unwrap();
+        assert!(matches!(cli.criteria.links, Some(SizeSpec::LessThan(3))));
+        let cli = Cli::try_parse_from(["find-rs", ".", "--links", "+1"]).unwrap();
+        assert!(matches!(cli.criteria.links, Some(SizeSpec::GreaterThan(1))));
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::Path,
+};
+
+use crate::{matcher::name::NamePattern, types::FileKind};
+
+// 读取文件头部的字节数，需要覆盖 tar 在 257 偏移处的 "ustar" 标记
+const SNIFF_LEN: usize = 512;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Class {
+    Executable,
+    Archive,
+    Other,
+}
+
+/// 魔数表：(偏移, 魔数, MIME 类型, 分类)
+const MAGIC: &[(usize, &[u8], &str, Class)] = &[
+    (0, b"\x7fELF", "application/x-executable", Class::Executable),
+    (0, b"\xfe\xed\xfa\xce", "application/x-mach-binary", Class::Executable),
+    (0, b"\xfe\xed\xfa\xcf", "application/x-mach-binary", Class::Executable),
+    (0, b"\xce\xfa\xed\xfe", "application/x-mach-binary", Class::Executable),
+    (0, b"\xcf\xfa\xed\xfe", "application/x-mach-binary", Class::Executable),
+    (0, b"MZ", "application/x-dosexec", Class::Executable),
+    (0, b"\x89PNG\r\n\x1a\n", "image/png", Class::Other),
+    (0, b"\xff\xd8\xff", "image/jpeg", Class::Other),
+    (0, b"GIF87a", "image/gif", Class::Other),
+    (0, b"GIF89a", "image/gif", Class::Other),
+    (0, b"%PDF-", "application/pdf", Class::Other),
+    (0, b"\x1f\x8b", "application/gzip", Class::Archive),
+    (0, b"PK\x03\x04", "application/zip", Class::Archive),
+    (0, b"PK\x05\x06", "application/zip", Class::Archive),
+    (0, b"BZh", "application/x-bzip2", Class::Archive),
+    (0, b"\xfd7zXZ\x00", "application/x-xz", Class::Archive),
+    (0, b"\x28\xb5\x2f\xfd", "application/zstd", Class::Archive),
+    (0, b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed", Class::Archive),
+    (0, b"Rar!\x1a\x07", "application/vnd.rar", Class::Archive),
+    (257, b"ustar", "application/x-tar", Class::Archive),
+];
+
+/// 嗅探得到的文件内容类型
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sniffed {
+    pub mime: &'static str,
+    class: Class,
+}
+
+impl Sniffed {
+    fn new(mime: &'static str, class: Class) -> Self {
+        Sniffed { mime, class }
+    }
+
+    pub fn is_kind(&self, kind: FileKind) -> bool {
+        match kind {
+            FileKind::Text => self.mime.starts_with("text/"),
+            FileKind::Binary => !self.mime.starts_with("text/") && !self.mime.starts_with("inode/"),
+            FileKind::Executable => {
+                self.class == Class::Executable || self.mime == "text/x-shellscript"
+            }
+            FileKind::Archive => self.class == Class::Archive,
+        }
+    }
+}
+
+/// 读取文件头部并判断内容类型，非普通文件返回 `inode/*` 类型
+pub fn sniff(path: &Path) -> Option<Sniffed> {
+    let metadata = fs::symlink_metadata(path).ok()?;
+    if metadata.is_dir() {
+        return Some(Sniffed::new("inode/directory", Class::Other));
+    }
+    if metadata.file_type().is_symlink() {
+        return Some(Sniffed::new("inode/symlink", Class::Other));
+    }
+    if !metadata.is_file() {
+        return Some(Sniffed::new("inode/special", Class::Other));
+    }
+
+    let mut buf = Vec::with_capacity(SNIFF_LEN);
+    File::open(path)
+        .ok()?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut buf)
+        .ok()?;
+
+    Some(sniff_bytes(&buf))
+}
+
+fn sniff_bytes(buf: &[u8]) -> Sniffed {
+    if buf.is_empty() {
+        return Sniffed::new("inode/x-empty", Class::Other);
+    }
+
+    for &(offset, magic, mime, class) in MAGIC {
+        if buf.get(offset..offset + magic.len()) == Some(magic) {
+            return Sniffed::new(mime, class);
+        }
+    }
+
+    if buf.len() >= 12 && &buf[..4] == b"RIFF" && &buf[8..12] == b"WEBP" {
+        return Sniffed::new("image/webp", Class::Other);
+    }
+
+    if !is_text(buf) {
+        return Sniffed::new("application/octet-stream", Class::Other);
+    }
+
+    if buf.starts_with(b"#!") {
+        return Sniffed::new("text/x-shellscript", Class::Other);
+    }
+
+    Sniffed::new("text/plain", Class::Other)
+}
+
+/// 不含 NUL 且是合法的 UTF-8（允许末尾被截断的字符）即视为文本
+fn is_text(buf: &[u8]) -> bool {
+    if buf.contains(&0) {
+        return false;
+    }
+
+    match std::str::from_utf8(buf) {
+        Ok(_) => true,
+        // error_len 为 None 表示只是末尾的多字节字符被截断
+        Err(e) => e.error_len().is_none() && buf.len() == SNIFF_LEN,
+    }
+}
+
+/// 匹配 MIME 类型，支持通配符，例如 `image/*`；模式应以忽略大小写的方式编译
+pub fn match_mime(path: &Path, pattern: &NamePattern) -> bool {
+    sniff(path).is_some_and(|sniffed| pattern.matches_str(sniffed.mime))
+}
+
+pub fn match_kind(path: &Path, kind: FileKind) -> bool {
+    sniff(path).is_some_and(|sniffed| sniffed.is_kind(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_magic_bytes() {
+        assert_eq!(sniff_bytes(b"\x7fELF\x02\x01\x01").mime, "application/x-executable");
+        assert_eq!(sniff_bytes(b"\x89PNG\r\n\x1a\n\0\0").mime, "image/png");
+        assert_eq!(sniff_bytes(b"\xff\xd8\xff\xe0").mime, "image/jpeg");
+        assert_eq!(sniff_bytes(b"\x1f\x8b\x08\x00").mime, "application/gzip");
+        assert_eq!(sniff_bytes(b"PK\x03\x04\x14\x00").mime, "application/zip");
+        assert_eq!(sniff_bytes(b"%PDF-1.7\n").mime, "application/pdf");
+        assert_eq!(sniff_bytes(b"#!/bin/sh\necho hi\n").mime, "text/x-shellscript");
+        assert_eq!(sniff_bytes("héllo\n".as_bytes()).mime, "text/plain");
+        assert_eq!(sniff_bytes(b"\x00\x01\x02\x03").mime, "application/octet-stream");
+
+        let mut tar = vec![0u8; SNIFF_LEN];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(sniff_bytes(&tar).mime, "application/x-tar");
+    }
+
+    #[test]
+    fn test_kinds() {
+        let elf = sniff_bytes(b"\x7fELF\x02\x01\x01");
+        assert!(elf.is_kind(FileKind::Executable));
+        assert!(elf.is_kind(FileKind::Binary));
+        assert!(!elf.is_kind(FileKind::Text));
+
+        let script = sniff_bytes(b"#!/usr/bin/env python3\n");
+        assert!(script.is_kind(FileKind::Executable));
+        assert!(script.is_kind(FileKind::Text));
+
+        let zip = sniff_bytes(b"PK\x03\x04");
+        assert!(zip.is_kind(FileKind::Archive));
+        assert!(!zip.is_kind(FileKind::Executable));
+
+        assert!(!sniff_bytes(b"").is_kind(FileKind::Binary));
+    }
+}
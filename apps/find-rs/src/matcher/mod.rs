@@ -1,14 +1,22 @@
+pub mod archive;
+pub mod empty;
+pub mod exclude;
+pub mod explain;
 pub mod filter_type;
 pub mod finder;
+pub mod fstype;
+pub mod git;
+pub mod hidden;
+pub mod inode;
+pub mod mime;
 pub mod mtime;
 pub mod name;
-pub mod parser;
+pub mod perm;
 pub mod regex;
 pub mod size;
 pub mod walker;
 
 // pub use filter_type::match_file_type;
 // pub use mtime::match_mtime;
-// pub use name::match_name;
 // pub use regex::simple_pattern_match;
 // pub use size::match_size;
\ No newline at end of file
@@ -15,4 +15,21 @@ pub fn match_mtime(path: &Path, time_spec: &TimeSpec) -> bool {
         },
         Err(_) => false,
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Cli;
+    use clap::Parser;
+    use std::time::Duration;
+
+    #[test]
+    fn test_zero_age_equality() {
+        // 不带运算符的 0s 表示“刚刚修改”，容差不能让时长下溢
+        let cli = Cli::try_parse_from(["find-rs", ".", "--mtime", "0s"]).unwrap();
+        let spec = cli.criteria.mtime.unwrap();
+        assert!(matches!(spec, TimeSpec::Equal(limit) if limit.is_zero()));
+        assert!(spec.matches(Duration::ZERO));
+        assert!(!spec.matches(Duration::from_secs(5)));
+    }
+}
@@ -1,53 +1,123 @@
-/// 匹配文件名，支持通配符 * 和 ?
-pub fn match_name(file_name: &str, pattern: &str, case_insensitive: bool) -> bool {
-    // 如果 pattern 包含通配符，使用通配符匹配
-    if pattern.contains('*') || pattern.contains('?') {
-        wildcard_match(file_name, pattern, case_insensitive)
-    } else {
-        // 否则使用简单的字符串匹配
-        if case_insensitive {
-            file_name.to_lowercase() == pattern.to_lowercase()
+use unicode_normalization::UnicodeNormalization;
+
+use crate::types::Normalization;
+
+/// 预先编译好的文件名模式，支持通配符 * 和 ?
+///
+/// 模式只在创建时做一次大小写折叠和规范化，之后每次匹配只处理文件名本身。
+#[derive(Clone, Debug)]
+pub struct NamePattern {
+    units: Vec<Unit>,
+    case_insensitive: bool,
+    normalization: Option<Normalization>,
+}
+
+impl NamePattern {
+    pub fn new(pattern: &str, case_insensitive: bool, normalization: Option<Normalization>) -> Self {
+        let mut name_pattern = NamePattern {
+            units: Vec::new(),
+            case_insensitive,
+            normalization,
+        };
+        name_pattern.units = name_pattern.decode_units(pattern.as_bytes());
+        name_pattern
+    }
+
+    pub fn matches_str(&self, file_name: &str) -> bool {
+        self.matches_bytes(file_name.as_bytes())
+    }
+
+    /// 匹配非 UTF-8 文件名（如 GBK、Latin-1 编码）
+    ///
+    /// 合法的 UTF-8 片段按字符比较，无法解码的字节逐个比较，`?` 匹配一个字符或一个字节。
+    pub fn matches_bytes(&self, file_name: &[u8]) -> bool {
+        let text = self.decode_units(file_name);
+        glob_match(&text, &self.units, Unit::Char('*'), Unit::Char('?'))
+    }
+
+    fn decode_units(&self, bytes: &[u8]) -> Vec<Unit> {
+        let mut units = Vec::with_capacity(bytes.len());
+        for chunk in bytes.utf8_chunks() {
+            let valid = chunk.valid();
+            if self.case_insensitive || self.normalization.is_some() {
+                units.extend(self.prepare(valid).chars().map(Unit::Char));
+            } else {
+                units.extend(valid.chars().map(Unit::Char));
+            }
+            units.extend(chunk.invalid().iter().map(|&b| Unit::Byte(b)));
+        }
+        units
+    }
+
+    /// 按配置对文本做 Unicode 规范化和完整的大小写折叠（如 ß → ss）
+    fn prepare(&self, text: &str) -> String {
+        let text = normalize(text, self.normalization);
+        if self.case_insensitive {
+            // 折叠可能破坏规范化形式，因此折叠后再规范化一次
+            normalize(&caseless::default_case_fold_str(&text), self.normalization)
         } else {
-            file_name == pattern
+            text
         }
     }
 }
 
-/// 简单的通配符匹配函数
-fn wildcard_match(text: &str, pattern: &str, case_insensitive: bool) -> bool {
-    let text = if case_insensitive {
-        text.to_lowercase()
-    } else {
-        text.to_string()
-    };
-    
-    let pattern = if case_insensitive {
-        pattern.to_lowercase()
-    } else {
-        pattern.to_string()
-    };
-    
-    // 简单的通配符匹配算法
+/// 将文本转换为指定的 Unicode 规范化形式
+pub fn normalize(text: &str, normalization: Option<Normalization>) -> String {
+    match normalization {
+        Some(Normalization::Nfc) => text.nfc().collect(),
+        Some(Normalization::Nfd) => text.nfd().collect(),
+        None => text.to_string(),
+    }
+}
+
+/// 判断模式中是否包含大写字母（用于 smart case）
+///
+/// 对于正则表达式，会跳过转义序列，例如 `\W`、`\S` 和 `\p{Lu}` 不算大写。
+pub fn has_uppercase(pattern: &str, is_regex: bool) -> bool {
+    if !is_regex {
+        return pattern.chars().any(char::is_uppercase);
+    }
+
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            // \pL 或 \p{Lu} 形式的 Unicode 类别
+            if matches!(chars.next(), Some('p' | 'P')) && chars.next() == Some('{') {
+                chars.by_ref().find(|&c| c == '}');
+            }
+        } else if c.is_uppercase() {
+            return true;
+        }
+    }
+    false
+}
+
+/// 文件名中的一个单元：合法的 UTF-8 字符，或无法解码的原始字节
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Unit {
+    Char(char),
+    Byte(u8),
+}
+
+/// 通用的通配符匹配算法，`star` 匹配任意长度，`any` 匹配单个元素
+fn glob_match<T: Copy + PartialEq>(text: &[T], pattern: &[T], star: T, any: T) -> bool {
     let mut text_index = 0;
     let mut pattern_index = 0;
-    let mut star_index = -1;
+    let mut star_index = None;
     let mut text_temp_index = 0;
     
-    let text_chars: Vec<char> = text.chars().collect();
-    let pattern_chars: Vec<char> = pattern.chars().collect();
-    
-    while text_index < text_chars.len() {
-        if pattern_index < pattern_chars.len() && 
-           (pattern_chars[pattern_index] == '?' || 
-            pattern_chars[pattern_index] == text_chars[text_index]) {
+    while text_index < text.len() {
+        if pattern_index < pattern.len() && 
+           (pattern[pattern_index] == any || 
+            pattern[pattern_index] == text[text_index]) {
             text_index += 1;
             pattern_index += 1;
-        } else if pattern_index < pattern_chars.len() && pattern_chars[pattern_index] == '*' {
-            star_index = pattern_index as isize;
+        } else if pattern_index < pattern.len() && pattern[pattern_index] == star {
+            star_index = Some(pattern_index);
             text_temp_index = text_index;
             pattern_index += 1;
-        } else if star_index != -1 {
-            pattern_index = (star_index + 1) as usize;
+        } else if let Some(star_index) = star_index {
+            pattern_index = star_index + 1;
             text_temp_index += 1;
             text_index = text_temp_index;
         } else {
@@ -56,17 +126,21 @@ fn wildcard_match(text: &str, pattern: &str, case_insensitive: bool) -> bool {
     }
     
     // 处理剩余的 '*' 字符
-    while pattern_index < pattern_chars.len() && pattern_chars[pattern_index] == '*' {
+    while pattern_index < pattern.len() && pattern[pattern_index] == star {
         pattern_index += 1;
     }
     
-    pattern_index == pattern_chars.len()
+    pattern_index == pattern.len()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn match_name(file_name: &str, pattern: &str, case_insensitive: bool) -> bool {
+        NamePattern::new(pattern, case_insensitive, None).matches_str(file_name)
+    }
+
     #[test]
     fn test_exact_match() {
         assert!(match_name("main.rs", "main.rs", false));
@@ -102,4 +176,47 @@ mod tests {
         assert!(match_name("abc123.txt", "abc???.txt", false));
         assert!(!match_name("abc12.txt", "abc???.txt", false));
     }
-}
\ No newline at end of file
+
+    fn match_name_bytes(file_name: &[u8], pattern: &str, case_insensitive: bool) -> bool {
+        NamePattern::new(pattern, case_insensitive, None).matches_bytes(file_name)
+    }
+
+    #[test]
+    fn test_non_utf8_names() {
+        // Latin-1 编码的 "café.txt"
+        assert!(match_name_bytes(b"caf\xe9.txt", "*.txt", false));
+        assert!(match_name_bytes(b"caf\xe9.txt", "caf?.txt", false));
+        assert!(!match_name_bytes(b"caf\xe9.txt", "ca?.txt", false));
+        assert!(match_name_bytes(b"CAF\xe9.TXT", "caf?.txt", true));
+        assert!(!match_name_bytes(b"CAF\xe9.TXT", "caf?.txt", false));
+
+        // GBK 编码的 "中文.log"，每个无法解码的字节都是一个单元
+        assert!(match_name_bytes(b"\xd6\xd0\xce\xc4.log", "*.log", false));
+        assert!(match_name_bytes(b"\xd6\xd0\xce\xc4.log", "????.log", false));
+    }
+
+    #[test]
+    fn test_full_case_folding() {
+        assert!(match_name("STRASSE.txt", "straße.txt", true));
+        assert!(match_name("ΣΊΣΥΦΟΣ", "σίσυφοσ", true));
+        assert!(match_name("Ǆ*", "ǆ*", true));
+    }
+
+    #[test]
+    fn test_normalization() {
+        // macOS 上创建的文件名通常是 NFD 形式
+        let nfd_name = "cafe\u{301}.txt";
+        assert!(!match_name(nfd_name, "café.txt", false));
+        assert!(NamePattern::new("café.txt", false, Some(Normalization::Nfc)).matches_str(nfd_name));
+        assert!(NamePattern::new("CAFÉ.*", true, Some(Normalization::Nfd)).matches_str(nfd_name));
+    }
+
+    #[test]
+    fn test_smart_case_detection() {
+        assert!(has_uppercase("Main.rs", false));
+        assert!(!has_uppercase("main.rs", false));
+        assert!(!has_uppercase(r"\W+\.rs", true));
+        assert!(!has_uppercase(r"\p{Lu}\d+", true));
+        assert!(has_uppercase(r"\p{Lu}Test", true));
+    }
+}
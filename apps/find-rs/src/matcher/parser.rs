@@ -1,7 +0,0 @@
-use clap::Parser;
-
-use crate::types::Cli;
-
-pub fn parse_cli() -> Cli {
-    Cli::parse()
-}
\ No newline at end of file
@@ -0,0 +1,83 @@
+use std::{fs::symlink_metadata, path::Path};
+
+use crate::{
+    actions::mode::ModeSpec,
+    types::FindResult,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PermMatch {
+    /// 权限位完全相同
+    Exact,
+    /// `-MODE`：包含所有指定的位
+    All,
+    /// `/MODE`：包含任意一个指定的位
+    Any,
+}
+
+/// `--perm` 的条件，与 GNU find 一样支持 `MODE`、`-MODE` 和 `/MODE`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PermSpec {
+    mode: u32,
+    kind: PermMatch,
+}
+
+impl PermSpec {
+    pub fn parse(spec: &str) -> FindResult<Self> {
+        let (kind, mode) = if let Some(mode) = spec.strip_prefix('-') {
+            (PermMatch::All, mode)
+        } else if let Some(mode) = spec.strip_prefix('/') {
+            (PermMatch::Any, mode)
+        } else {
+            (PermMatch::Exact, spec)
+        };
+
+        // 符号形式按作用在 000 上的结果计算，例如 `o+w` 即 002
+        let mode = ModeSpec::parse(mode)?.apply(0, false);
+        Ok(PermSpec { mode, kind })
+    }
+
+    pub fn matches(&self, mode: u32) -> bool {
+        let mode = mode & 0o7777;
+        match self.kind {
+            PermMatch::Exact => mode == self.mode,
+            PermMatch::All => mode & self.mode == self.mode,
+            // 与 GNU find 一致，`/000` 匹配所有文件
+            PermMatch::Any => self.mode == 0 || mode & self.mode != 0,
+        }
+    }
+}
+
+#[cfg(unix)]
+pub fn match_perm(path: &Path, spec: &PermSpec) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    symlink_metadata(path).is_ok_and(|m| spec.matches(m.permissions().mode()))
+}
+
+#[cfg(not(unix))]
+pub fn match_perm(_path: &Path, _spec: &PermSpec) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perm_specs() {
+        let exact = PermSpec::parse("644").unwrap();
+        assert!(exact.matches(0o100644));
+        assert!(!exact.matches(0o664));
+
+        let all = PermSpec::parse("-u+x,g+x").unwrap();
+        assert!(all.matches(0o750));
+        assert!(!all.matches(0o740));
+
+        let any = PermSpec::parse("/o+w").unwrap();
+        assert!(any.matches(0o666));
+        assert!(any.matches(0o602));
+        assert!(!any.matches(0o664));
+        assert!(PermSpec::parse("/000").unwrap().matches(0o600));
+    }
+}
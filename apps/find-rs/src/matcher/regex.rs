@@ -1,15 +1,21 @@
-use regex::Regex;
+use regex::bytes::Regex;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::Mutex;
 
 // 线程安全的正则表达式缓存
+// 统一使用字节正则，UTF-8 与非 UTF-8 文件名共用同一套编译结果
 static REGEX_CACHE: Lazy<Mutex<HashMap<String, Regex>>> = Lazy::new(|| {
     Mutex::new(HashMap::new())
 });
 
 /// 正则表达式匹配（用于 -regex 选项）
 pub fn regex_match(text: &str, pattern: &str, case_insensitive: bool) -> bool {
+    regex_match_bytes(text.as_bytes(), pattern, case_insensitive)
+}
+
+/// 对原始字节进行正则匹配，可用 `(?-u:\xff)` 等写法匹配非 UTF-8 字节
+pub fn regex_match_bytes(text: &[u8], pattern: &str, case_insensitive: bool) -> bool {
     let cache_key = if case_insensitive {
         format!("(?i){}", pattern)
     } else {
@@ -51,4 +57,12 @@ mod tests {
         assert!(regex_match("abc123", r"[a-z]+\d+", false));
         assert!(!regex_match("ABC123", r"[a-z]+\d+", false));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_regex_match_bytes() {
+        assert!(regex_match_bytes(b"caf\xe9.txt", r"\.txt$", false));
+        assert!(regex_match_bytes(b"caf\xe9.txt", r"^caf(?-u:\xe9)", false));
+        assert!(regex_match_bytes(b"CAF\xe9.TXT", r"^caf(?-u:.)\.txt$", true));
+        assert!(!regex_match_bytes(b"caf\xe9.txt", r"^cafe", false));
+    }
+}
@@ -1,30 +1,187 @@
-use walkdir::WalkDir;
-use crate::types::Cli;
+use std::collections::VecDeque;
 
-pub fn get_walker(cli: &Cli) -> WalkDir {
+use walkdir::{DirEntry, FilterEntry, WalkDir};
+
+use crate::{
+    matcher::{exclude::ExcludeRules, explain::Explainer, hidden::HiddenRules},
+    types::Cli,
+};
+
+type Pruned = FilterEntry<walkdir::IntoIter, Box<dyn FnMut(&DirEntry) -> bool>>;
+
+/// 带剪枝规则的遍历器
+///
+/// 底层总是先序遍历，这样隐藏和排除规则才能在进入目录前生效。walkdir 的
+/// `contents_first` 会在条目被拒绝时跳过其父目录的剩余内容，`min_depth` 以下的条目
+/// 也不会经过剪枝判断，因此后序输出和最小深度都在这里处理。
+pub struct Walker {
+    inner: Pruned,
+    min_depth: usize,
+    post_order: bool,
+    /// 后序遍历时尚未输出的祖先目录，越深越靠后
+    open_dirs: Vec<DirEntry>,
+    ready: VecDeque<walkdir::Result<DirEntry>>,
+}
+
+impl Walker {
+    /// 不再进入最近输出的目录（`--prune`），只在先序遍历时有效
+    pub fn skip_current_dir(&mut self) {
+        if !self.post_order {
+            self.inner.skip_current_dir();
+        }
+    }
+
+    /// 深度不小于 `depth` 的目录已经遍历完毕，依次输出
+    fn close_dirs(&mut self, depth: usize) {
+        while self.open_dirs.last().is_some_and(|dir| dir.depth() >= depth) {
+            let dir = self.open_dirs.pop().unwrap();
+            if dir.depth() >= self.min_depth {
+                self.ready.push_back(Ok(dir));
+            }
+        }
+    }
+}
+
+impl Iterator for Walker {
+    type Item = walkdir::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.ready.pop_front() {
+                return Some(item);
+            }
+
+            let Some(item) = self.inner.next() else {
+                if self.open_dirs.is_empty() {
+                    return None;
+                }
+                self.close_dirs(0);
+                continue;
+            };
+            let entry = match item {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if !self.post_order {
+                // 比最小深度浅的条目不输出，但仍然进入其中
+                if entry.depth() >= self.min_depth {
+                    return Some(Ok(entry));
+                }
+                continue;
+            }
+
+            self.close_dirs(entry.depth());
+            if entry.file_type().is_dir() {
+                self.open_dirs.push(entry);
+            } else if entry.depth() >= self.min_depth {
+                self.ready.push_back(Ok(entry));
+            }
+        }
+    }
+}
+
+/// 给出 `explainer` 时，被剪枝的条目会通过它报告（`--explain`）
+pub fn get_walker(cli: &Cli, explainer: Option<&Explainer>) -> Walker {
     let mut walker = WalkDir::new(&cli.path);
     
-    // 重要：设置 min_depth 为 0 以包含起始路径本身
-    // 但要注意，如果起始路径是文件，min_depth(0) 会包含它
-    // 如果起始路径是目录，min_depth(0) 会包含目录本身
-    
     // 处理深度限制
     if let Some(depth) = cli.criteria.depth {
         // 如果指定了深度，设置最大深度
         // 注意：深度是相对于起始路径的
         // depth=0: 只包含起始路径本身
         // depth=1: 包含起始路径的直接子项
-        walker = walker.max_depth(depth as usize);
+        walker = walker.max_depth(depth);
     }
     
-    // 注意：我们不设置 min_depth，默认就是 0
-    
     if cli.criteria.follow_symlinks {
         walker = walker.follow_links(true);
     }
+
+    // 比较设备号，不跨越挂载点
+    if cli.criteria.one_file_system {
+        walker = walker.same_file_system(true);
+    }
     
     // 添加内容排序，以便输出更可预测
     walker = walker.sort_by(|a, b| a.file_name().cmp(b.file_name()));
 
-    walker
-}
\ No newline at end of file
+    // 在遍历阶段剪枝：隐藏或被排除的目录不会被打开，其下的内容也不会出现
+    let hidden = HiddenRules::from_criteria(&cli.criteria);
+    let exclude = ExcludeRules::from_cli(cli);
+    let explainer = explainer.cloned();
+    let prune: Box<dyn FnMut(&DirEntry) -> bool> = Box::new(move |entry| {
+        let reason = if hidden.is_hidden(entry) {
+            "hidden"
+        } else if exclude.is_excluded(entry) {
+            "excluded"
+        } else {
+            return true;
+        };
+        if let Some(explainer) = &explainer {
+            explainer.report_skipped(entry.path(), entry.file_type().is_dir(), reason);
+        }
+        false
+    });
+
+    Walker {
+        inner: walker.into_iter().filter_entry(prune),
+        // 未指定 min_depth 时默认为 0，即包含起始路径本身
+        min_depth: cli.criteria.min_depth.unwrap_or(0),
+        // 后序遍历：先输出目录内容，再输出目录本身
+        post_order: cli.criteria.post_order,
+        open_dirs: Vec::new(),
+        ready: VecDeque::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn walk_depths(args: &[&str]) -> Vec<usize> {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("a/b/c")).unwrap();
+
+        let root = dir.path().to_str().unwrap();
+        let cli = Cli::try_parse_from([&["find-rs", root], args].concat()).unwrap();
+        get_walker(&cli, None).filter_map(|e| e.ok()).map(|e| e.depth()).collect()
+    }
+
+    /// 含隐藏目录和被排除目录的树，返回相对路径
+    fn walk_paths(args: &[&str]) -> Vec<String> {
+        let dir = TempDir::new().unwrap();
+        for path in [".git/objects/x", "target/debug/y", "src/a.rs"] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let root = dir.path().to_str().unwrap();
+        let cli = Cli::try_parse_from([&["find-rs", root], args].concat()).unwrap();
+        get_walker(&cli, None)
+            .filter_map(|e| e.ok())
+            .map(|e| e.path().strip_prefix(dir.path()).unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_min_and_max_depth() {
+        assert_eq!(walk_depths(&[]), vec![0, 1, 2, 3]);
+        assert_eq!(walk_depths(&["--min-depth", "1"]), vec![1, 2, 3]);
+        assert_eq!(walk_depths(&["--min-depth", "2", "--max-depth", "2"]), vec![2]);
+        assert_eq!(walk_depths(&["-d", "1"]), vec![0, 1]);
+
+        // 比最小深度浅的隐藏或被排除目录同样会被剪枝
+        assert_eq!(walk_paths(&["--min-depth", "2", "-E", "target"]), ["src/a.rs"]);
+    }
+
+    #[test]
+    fn test_post_order_keeps_pruning() {
+        assert_eq!(walk_paths(&["--post-order", "-E", "target"]), ["src/a.rs", "src", ""]);
+        assert_eq!(walk_paths(&["--post-order", "--min-depth", "1", "-E", "target"]), ["src/a.rs", "src"]);
+    }
+}
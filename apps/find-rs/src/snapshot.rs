@@ -0,0 +1,517 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use clap::{parser::ValueSource, ArgMatches, Args, FromArgMatches, ValueEnum};
+use rayon::prelude::*;
+
+use crate::{
+    actions::{checksum::hash_file, format::type_char},
+    index::build::mode_of,
+    matcher::finder::find_files,
+    types::{Cli, FileActions, FindError, FindResult, HashAlgorithm, SearchCriteria, SnapshotCommand},
+};
+
+const HEADER: &str = "# find-rs snapshot 1";
+
+/// 快照中的一个条目
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapshotEntry {
+    /// 与 `%y` 相同的类型字符
+    pub kind: char,
+    pub size: u64,
+    pub modified: SystemTime,
+    pub mode: u32,
+    pub hash: Option<String>,
+}
+
+/// 一次搜索结果的快照，路径相对于搜索起点，便于比较不同主机或目录
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub root: PathBuf,
+    pub hash: Option<HashAlgorithm>,
+    /// 保存时命令行上的搜索条件；较早的快照没有记录
+    pub args: Option<Vec<String>>,
+    pub entries: BTreeMap<PathBuf, SnapshotEntry>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Added(PathBuf),
+    Removed(PathBuf),
+    /// 路径和发生变化的字段
+    Modified(PathBuf, Vec<&'static str>),
+    Renamed(PathBuf, PathBuf),
+}
+
+impl Change {
+    fn path(&self) -> &Path {
+        match self {
+            Change::Added(path) | Change::Removed(path) | Change::Modified(path, _) => path,
+            Change::Renamed(from, _) => from,
+        }
+    }
+}
+
+impl Snapshot {
+    /// 对 `root` 执行一次搜索并记录所有匹配项，指定算法时并行计算普通文件的摘要
+    pub fn capture(root: &Path, criteria: &SearchCriteria, hash: Option<HashAlgorithm>) -> FindResult<Self> {
+        let cli = Cli {
+            command: None,
+            path: root.to_path_buf(),
+            criteria: criteria.clone(),
+            actions: FileActions::default(),
+            no_config: false,
+            explain: None,
+        };
+        let matches = find_files(&cli)?;
+
+        let entries = matches
+            .par_iter()
+            .filter(|file| file.member.is_none())
+            .filter_map(|file| {
+                let relative = match file.path.strip_prefix(root) {
+                    Ok(relative) if !relative.as_os_str().is_empty() => relative.to_path_buf(),
+                    // 搜索起点本身只在它是文件时记录
+                    _ if file.depth == 0 && !file.path.is_dir() => PathBuf::from(file.path.file_name()?),
+                    _ => return None,
+                };
+
+                let metadata = match fs::symlink_metadata(&file.path) {
+                    Ok(metadata) => metadata,
+                    Err(e) => {
+                        eprintln!("Warning: Cannot stat {}: {}", file.path.display(), e);
+                        return None;
+                    }
+                };
+                let hash = match hash {
+                    Some(algorithm) if metadata.is_file() => match hash_file(&file.path, algorithm) {
+                        Ok(sum) => Some(sum),
+                        Err(e) => {
+                            eprintln!("Warning: Failed to hash {}: {}", file.path.display(), e);
+                            None
+                        }
+                    },
+                    _ => None,
+                };
+
+                let entry = SnapshotEntry {
+                    kind: type_char(&metadata),
+                    size: metadata.len(),
+                    modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    mode: mode_of(&metadata),
+                    hash,
+                };
+                Some((relative, entry))
+            })
+            .collect();
+
+        Ok(Snapshot {
+            root: fs::canonicalize(root)?,
+            hash,
+            args: None,
+            entries,
+        })
+    }
+
+    /// 写入制表符分隔的文本：类型、大小、修改时间、权限、摘要和路径
+    pub fn save(&self, path: &Path) -> FindResult<()> {
+        let mut output = String::new();
+        let _ = writeln!(output, "{}", HEADER);
+        let _ = writeln!(output, "# root\t{}", escape(&self.root.to_string_lossy()));
+        if let Some(algorithm) = self.hash {
+            let _ = writeln!(output, "# hash\t{}", algorithm_name(algorithm));
+        }
+        if let Some(args) = &self.args {
+            let args: Vec<String> = args.iter().map(|arg| escape(arg)).collect();
+            let _ = writeln!(output, "# args\t{}", args.join("\t"));
+        }
+
+        for (path, entry) in &self.entries {
+            let modified = entry.modified.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+            let _ = writeln!(
+                output,
+                "{}\t{}\t{}.{:09}\t{:o}\t{}\t{}",
+                entry.kind,
+                entry.size,
+                modified.as_secs(),
+                modified.subsec_nanos(),
+                entry.mode,
+                entry.hash.as_deref().unwrap_or("-"),
+                escape(&path.to_string_lossy())
+            );
+        }
+
+        fs::write(path, output)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> FindResult<Self> {
+        let content = fs::read_to_string(path)?;
+        let invalid = |line: usize, reason: &str| {
+            FindError::Snapshot(format!("{}:{}: {}", path.display(), line, reason))
+        };
+
+        let mut lines = content.lines().enumerate();
+        if lines.next().map(|(_, line)| line) != Some(HEADER) {
+            return Err(invalid(1, "not a find-rs snapshot"));
+        }
+
+        let mut snapshot = Snapshot {
+            root: PathBuf::new(),
+            hash: None,
+            args: None,
+            entries: BTreeMap::new(),
+        };
+
+        for (index, line) in lines {
+            let line_no = index + 1;
+            if let Some(header) = line.strip_prefix("# ") {
+                match header.split_once('\t') {
+                    Some(("root", root)) => snapshot.root = PathBuf::from(unescape(root)),
+                    Some(("hash", name)) => {
+                        snapshot.hash = Some(
+                            HashAlgorithm::from_str(name, true).map_err(|_| invalid(line_no, "unknown hash algorithm"))?,
+                        )
+                    }
+                    Some(("args", args)) => {
+                        snapshot.args = Some(args.split('\t').filter(|arg| !arg.is_empty()).map(unescape).collect())
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            let (path, entry) = parse_entry(line).ok_or_else(|| invalid(line_no, "malformed entry"))?;
+            snapshot.entries.insert(path, entry);
+        }
+
+        Ok(snapshot)
+    }
+}
+
+fn parse_entry(line: &str) -> Option<(PathBuf, SnapshotEntry)> {
+    let mut fields = line.splitn(6, '\t');
+    let kind = fields.next()?.chars().next()?;
+    let size = fields.next()?.parse().ok()?;
+    let (secs, nanos) = fields.next()?.split_once('.')?;
+    let modified = SystemTime::UNIX_EPOCH + Duration::new(secs.parse().ok()?, nanos.parse().ok()?);
+    let mode = u32::from_str_radix(fields.next()?, 8).ok()?;
+    let hash = match fields.next()? {
+        "-" => None,
+        hash => Some(hash.to_string()),
+    };
+    let path = PathBuf::from(unescape(fields.next()?));
+
+    Some((path, SnapshotEntry { kind, size, modified, mode, hash }))
+}
+
+/// 比较两个快照；内容摘要相同的删除与新增文件视为重命名
+pub fn diff(old: &Snapshot, new: &Snapshot) -> Vec<Change> {
+    // 算法不同的摘要无法比较
+    let compare_hashes = old.hash.is_some() && old.hash == new.hash;
+
+    let mut changes = Vec::new();
+    let mut removed = Vec::new();
+    for (path, before) in &old.entries {
+        match new.entries.get(path) {
+            Some(after) => {
+                let fields = changed_fields(before, after, compare_hashes);
+                if !fields.is_empty() {
+                    changes.push(Change::Modified(path.clone(), fields));
+                }
+            }
+            None => removed.push(path),
+        }
+    }
+    let mut added: Vec<&PathBuf> = new.entries.keys().filter(|path| !old.entries.contains_key(*path)).collect();
+
+    if compare_hashes {
+        // 每个摘要按路径顺序一一配对，避免多个相同内容的文件重复匹配
+        let mut added_by_hash: HashMap<&str, Vec<&PathBuf>> = HashMap::new();
+        for path in added.iter().rev() {
+            if let Some(hash) = new.entries[*path].hash.as_deref() {
+                added_by_hash.entry(hash).or_default().push(path);
+            }
+        }
+
+        removed.retain(|path| {
+            let target = old.entries[*path]
+                .hash
+                .as_deref()
+                .and_then(|hash| added_by_hash.get_mut(hash))
+                .and_then(|candidates| candidates.pop());
+            match target {
+                Some(target) => {
+                    changes.push(Change::Renamed((*path).clone(), target.clone()));
+                    false
+                }
+                None => true,
+            }
+        });
+
+        let renamed_to: HashSet<&PathBuf> = changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::Renamed(_, to) => new.entries.get_key_value(to).map(|(key, _)| key),
+                _ => None,
+            })
+            .collect();
+        added.retain(|path| !renamed_to.contains(path));
+    }
+
+    changes.extend(removed.into_iter().map(|path| Change::Removed(path.clone())));
+    changes.extend(added.into_iter().map(|path| Change::Added(path.clone())));
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+    changes
+}
+
+fn changed_fields(before: &SnapshotEntry, after: &SnapshotEntry, compare_hashes: bool) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if before.kind != after.kind {
+        fields.push("type");
+    }
+    if before.mode != after.mode {
+        fields.push("mode");
+    }
+    // 目录的大小和修改时间随内容变化，单独比较没有意义
+    if before.kind != 'd' || after.kind != 'd' {
+        if before.size != after.size {
+            fields.push("size");
+        }
+        if before.modified != after.modified {
+            fields.push("mtime");
+        }
+        if compare_hashes && before.hash != after.hash {
+            fields.push("content");
+        }
+    }
+    fields
+}
+
+/// 执行 `find-rs snapshot` 子命令
+pub fn run(command: &SnapshotCommand) -> FindResult<()> {
+    match command {
+        SnapshotCommand::Save { file, path, hash, criteria, args } => {
+            let mut snapshot = Snapshot::capture(path, criteria, *hash)?;
+            snapshot.args = Some(args.clone());
+            snapshot.save(file)?;
+            eprintln!("Saved {} entries under {} to {}", snapshot.entries.len(), snapshot.root.display(), file.display());
+            Ok(())
+        }
+        SnapshotCommand::Diff { old, new, criteria } => {
+            let old = Snapshot::load(old)?;
+            let new = match new {
+                Some(new) => Snapshot::load(new)?,
+                // 与当前文件系统比较时使用快照记录的根目录、搜索条件和摘要算法
+                None => match &old.args {
+                    Some(args) => Snapshot::capture(&old.root, &parse_criteria(args)?, old.hash)?,
+                    None => Snapshot::capture(&old.root, criteria, old.hash)?,
+                },
+            };
+            if old.hash.is_some() && new.hash.is_some() && old.hash != new.hash {
+                eprintln!("Warning: Snapshots use different hash algorithms, content changes and renames are not detected");
+            }
+
+            let changes = diff(&old, &new);
+            let mut counts = [0usize; 4];
+            for change in &changes {
+                match change {
+                    Change::Added(path) => {
+                        counts[0] += 1;
+                        println!("+ {}", path.display());
+                    }
+                    Change::Removed(path) => {
+                        counts[1] += 1;
+                        println!("- {}", path.display());
+                    }
+                    Change::Modified(path, fields) => {
+                        counts[2] += 1;
+                        println!("M {} ({})", path.display(), fields.join(", "));
+                    }
+                    Change::Renamed(from, to) => {
+                        counts[3] += 1;
+                        println!("R {} -> {}", from.display(), to.display());
+                    }
+                }
+            }
+
+            eprintln!(
+                "{} added, {} removed, {} modified, {} renamed",
+                counts[0], counts[1], counts[2], counts[3]
+            );
+            Ok(())
+        }
+    }
+}
+
+/// 把命令行上给出的搜索条件还原为参数，写入快照后可由 `parse_criteria` 重新解析
+pub fn criteria_args(matches: &ArgMatches) -> Vec<String> {
+    let command = SearchCriteria::augment_args(clap::Command::new("criteria"));
+    let mut args = Vec::new();
+    for arg in command.get_arguments() {
+        let id = arg.get_id().as_str();
+        let Some(long) = arg.get_long().filter(|_| matches.value_source(id) == Some(ValueSource::CommandLine)) else {
+            continue;
+        };
+        if !arg.get_action().takes_values() {
+            args.push(format!("--{}", long));
+            continue;
+        }
+        // 使用 `--name=VALUE` 的写法，以 `-` 开头的值也不会被当作选项
+        for occurrence in matches.get_raw_occurrences(id).into_iter().flatten() {
+            let values: Vec<String> = occurrence.map(|value| value.to_string_lossy().into_owned()).collect();
+            match arg.get_value_delimiter() {
+                Some(delimiter) => args.push(format!("--{}={}", long, values.join(&delimiter.to_string()))),
+                None => args.extend(values.iter().map(|value| format!("--{}={}", long, value))),
+            }
+        }
+    }
+    args
+}
+
+fn parse_criteria(args: &[String]) -> FindResult<SearchCriteria> {
+    let command = SearchCriteria::augment_args(clap::Command::new("criteria").no_binary_name(true));
+    command
+        .try_get_matches_from(args)
+        .and_then(|matches| SearchCriteria::from_arg_matches(&matches))
+        .map_err(|e| {
+            let message = e.to_string();
+            let reason = message.lines().next().unwrap_or_default().trim_start_matches("error: ");
+            FindError::Snapshot(format!("invalid recorded search criteria: {}", reason))
+        })
+}
+
+fn algorithm_name(algorithm: HashAlgorithm) -> String {
+    algorithm
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// 转义路径中的反斜杠、制表符和换行，使每个条目占一行
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut output = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => output.push('\t'),
+            Some('n') => output.push('\n'),
+            Some(other) => output.push(other),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    fn entry(size: u64, hash: &str) -> SnapshotEntry {
+        SnapshotEntry {
+            kind: 'f',
+            size,
+            modified: SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 5),
+            mode: 0o644,
+            hash: Some(hash.to_string()),
+        }
+    }
+
+    fn snapshot(entries: &[(&str, SnapshotEntry)]) -> Snapshot {
+        Snapshot {
+            root: PathBuf::from("/srv/build"),
+            hash: Some(HashAlgorithm::Sha256),
+            args: Some(vec!["--name=*.log".to_string(), "--exclude=a\tb".to_string()]),
+            entries: entries.iter().map(|(p, e)| (PathBuf::from(p), e.clone())).collect(),
+        }
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("before.snap");
+        let original = snapshot(&[("bin/app", entry(10, "aa")), ("odd\tname\\x\n", entry(0, "bb"))]);
+
+        original.save(&file).unwrap();
+        assert_eq!(Snapshot::load(&file).unwrap(), original);
+
+        fs::write(&file, "garbage").unwrap();
+        assert!(matches!(Snapshot::load(&file), Err(FindError::Snapshot(_))));
+    }
+
+    #[test]
+    fn test_diff_detects_all_change_kinds() {
+        let old = snapshot(&[
+            ("keep", entry(1, "k")),
+            ("grow", entry(1, "g1")),
+            ("gone", entry(1, "x")),
+            ("old-name", entry(5, "r")),
+        ]);
+        let new = snapshot(&[
+            ("keep", entry(1, "k")),
+            ("grow", entry(2, "g2")),
+            ("new", entry(1, "n")),
+            ("new-name", entry(5, "r")),
+        ]);
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                Change::Removed(PathBuf::from("gone")),
+                Change::Modified(PathBuf::from("grow"), vec!["size", "content"]),
+                Change::Added(PathBuf::from("new")),
+                Change::Renamed(PathBuf::from("old-name"), PathBuf::from("new-name")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_criteria_args_round_trip() {
+        use clap::CommandFactory;
+
+        let args = ["snapshot", "save", "out.snap", ".", "-n", "*.log", "-E", "target", "--links", "-3", "--all"];
+        let matches = Cli::command().try_get_matches_from(["find-rs"].iter().chain(&args)).unwrap();
+        let save = matches.subcommand_matches("snapshot").and_then(|m| m.subcommand_matches("save")).unwrap();
+        let args = criteria_args(save);
+        assert_eq!(args, ["--name=*.log", "--links=-3", "--all", "--exclude=target"]);
+
+        let criteria = parse_criteria(&args).unwrap();
+        assert_eq!(criteria.name.as_deref(), Some("*.log"));
+        assert_eq!(criteria.excludes, ["target"]);
+        assert!(criteria.all);
+        assert!(matches!(parse_criteria(&["--bogus".to_string()]), Err(FindError::Snapshot(_))));
+    }
+
+    #[test]
+    fn test_capture_live_tree() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("etc")).unwrap();
+        fs::write(dir.path().join("etc/app.conf"), "abc").unwrap();
+        fs::write(dir.path().join(".hidden"), "").unwrap();
+
+        let cli = Cli::try_parse_from(["find-rs"]).unwrap();
+        let snapshot = Snapshot::capture(dir.path(), &cli.criteria, Some(HashAlgorithm::Md5)).unwrap();
+
+        let paths: Vec<_> = snapshot.entries.keys().cloned().collect();
+        assert_eq!(paths, vec![PathBuf::from("etc"), PathBuf::from("etc/app.conf")]);
+        let conf = &snapshot.entries[Path::new("etc/app.conf")];
+        assert_eq!(conf.kind, 'f');
+        assert_eq!(conf.hash.as_deref(), Some("900150983cd24fb0d6963f7d28e17f72"));
+        assert_eq!(snapshot.entries[Path::new("etc")].hash, None);
+    }
+}
@@ -0,0 +1,442 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs,
+    time::Duration,
+};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::{
+    actions::{
+        delete,
+        du::DuTree,
+        print::format_human_size,
+    },
+    tui::{Term, TerminalSession},
+    types::{Cli, FindError, FindResult, FoundFile},
+};
+
+/// 百分比条的宽度（字符数）
+const BAR_WIDTH: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SortBy {
+    Size,
+    Name,
+}
+
+/// 退出浏览器时的结果
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    /// 正常退出，删除已标记的条目（可能为空）
+    Quit,
+    /// Ctrl-C：放弃所有标记
+    Abort,
+}
+
+/// 目录浏览器的状态，与终端绘制分离以便测试
+struct Browser {
+    tree: DuTree,
+    /// 当前所在目录的节点
+    dir: usize,
+    cursor: usize,
+    offset: usize,
+    page: usize,
+    /// 进入子目录前的光标位置，返回上级时恢复
+    history: Vec<usize>,
+    sort: SortBy,
+    marked: BTreeSet<usize>,
+}
+
+/// `--du --tui`：按大小浏览目录树，标记的条目在退出后逐个确认删除
+pub fn run(matches: &[FoundFile], cli: &Cli) -> FindResult<()> {
+    let mut browser = Browser::new(DuTree::build(matches, &cli.path));
+    let outcome = {
+        let mut session = TerminalSession::start()?;
+        event_loop(&mut session.terminal, &mut browser)?
+    };
+    if outcome == Outcome::Abort {
+        return Ok(());
+    }
+
+    let targets = browser.deletion_targets();
+    if targets.is_empty() {
+        return Ok(());
+    }
+    let total: u64 = targets.iter().map(|index| browser.tree.nodes[*index].size).sum();
+    eprintln!("{} entries marked for deletion ({} of matched files)", targets.len(), format_human_size(total));
+
+    let mut freed = 0;
+    for index in targets {
+        let node = &browser.tree.nodes[index];
+        if node.is_dir {
+            freed += delete_matched_under(&browser.tree, index);
+            continue;
+        }
+        match delete::delete_file(&node.path, false) {
+            Ok(()) if fs::symlink_metadata(&node.path).is_err() => freed += node.size,
+            // 删除失败已经输出了原因，拒绝删除则跳过这一项
+            Ok(()) | Err(FindError::NoAction) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    eprintln!("Freed {}", format_human_size(freed));
+    Ok(())
+}
+
+/// 标记的目录只删除其下匹配到的文件，未匹配的文件保留；删空的目录随后移除。
+/// 整个目录只确认一次，返回实际释放的字节数。
+fn delete_matched_under(tree: &DuTree, index: usize) -> u64 {
+    let node = &tree.nodes[index];
+    let prompt = format!(
+        "Delete {} matched file(s) ({}) under {}? Unmatched files are kept.",
+        node.files,
+        format_human_size(node.size),
+        node.path.display()
+    );
+    if !delete::confirm(&prompt) {
+        return 0;
+    }
+    remove_matched_under(tree, index)
+}
+
+/// 已经确认过，逐项删除时不再询问。只移除本次删除后变空的目录，
+/// 原本就为空的目录（例如被匹配到的空目录）保留
+fn remove_matched_under(tree: &DuTree, index: usize) -> u64 {
+    let subtree = tree.subtree(index);
+    let mut freed = 0;
+    let mut emptied = HashSet::new();
+    for file in subtree.iter().map(|i| &tree.nodes[*i]).filter(|n| !n.is_dir) {
+        if delete::delete_file(&file.path, true).is_ok() && fs::symlink_metadata(&file.path).is_err() {
+            freed += file.size;
+            emptied.extend(file.parent);
+        }
+    }
+    // subtree 按广度优先排列，倒序即由深到浅，子目录移除后再检查其上级
+    for &i in subtree.iter().rev() {
+        let dir = &tree.nodes[i];
+        if !dir.is_dir || !emptied.contains(&i) {
+            continue;
+        }
+        if delete::delete_empty_dir(&dir.path, true).is_ok() && fs::symlink_metadata(&dir.path).is_err() {
+            emptied.extend(dir.parent);
+        }
+    }
+    freed
+}
+
+fn event_loop(terminal: &mut Term, browser: &mut Browser) -> FindResult<Outcome> {
+    loop {
+        terminal.draw(|frame| browser.render(frame))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                if let Some(outcome) = browser.handle_key(key) {
+                    return Ok(outcome);
+                }
+            }
+        }
+        // 连续的按键（例如按住方向键）一起处理后再重绘
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    if let Some(outcome) = browser.handle_key(key) {
+                        return Ok(outcome);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Browser {
+    fn new(tree: DuTree) -> Self {
+        Browser {
+            tree,
+            dir: 0,
+            cursor: 0,
+            offset: 0,
+            page: 10,
+            history: Vec::new(),
+            sort: SortBy::Size,
+            marked: BTreeSet::new(),
+        }
+    }
+
+    /// 当前目录的子项，按当前排序方式排列
+    fn entries(&self) -> Vec<usize> {
+        let nodes = &self.tree.nodes;
+        let mut entries = nodes[self.dir].children.clone();
+        match self.sort {
+            SortBy::Size => entries.sort_by(|a, b| {
+                nodes[*b].size.cmp(&nodes[*a].size).then_with(|| nodes[*a].name.cmp(&nodes[*b].name))
+            }),
+            SortBy::Name => entries.sort_by(|a, b| {
+                nodes[*b].is_dir.cmp(&nodes[*a].is_dir).then_with(|| nodes[*a].name.cmp(&nodes[*b].name))
+            }),
+        }
+        entries
+    }
+
+    fn current(&self) -> Option<usize> {
+        self.entries().get(self.cursor).copied()
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let last = self.tree.nodes[self.dir].children.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    fn enter(&mut self) {
+        if let Some(index) = self.current().filter(|index| self.tree.nodes[*index].is_dir) {
+            self.history.push(self.cursor);
+            self.dir = index;
+            self.cursor = 0;
+            self.offset = 0;
+        }
+    }
+
+    fn leave(&mut self) {
+        let Some(parent) = self.tree.nodes[self.dir].parent else {
+            return;
+        };
+        let child = self.dir;
+        self.dir = parent;
+        // 优先恢复进入前的位置，排序改变过时重新定位到刚离开的目录
+        let remembered = self.history.pop();
+        self.cursor = match remembered.filter(|pos| self.entries().get(*pos) == Some(&child)) {
+            Some(pos) => pos,
+            None => self.entries().iter().position(|index| *index == child).unwrap_or(0),
+        };
+        self.offset = 0;
+    }
+
+    fn toggle_mark(&mut self) {
+        if let Some(index) = self.current() {
+            if !self.marked.remove(&index) {
+                self.marked.insert(index);
+            }
+            self.move_cursor(1);
+        }
+    }
+
+    /// 需要删除的条目：已被上级目录的标记覆盖的条目不再单独删除
+    fn deletion_targets(&self) -> Vec<usize> {
+        let mut targets: Vec<usize> = self
+            .marked
+            .iter()
+            .copied()
+            .filter(|index| {
+                !self
+                    .marked
+                    .iter()
+                    .any(|other| other != index && self.tree.is_within(*index, *other))
+            })
+            .collect();
+        targets.sort_by(|a, b| self.tree.nodes[*a].path.cmp(&self.tree.nodes[*b].path));
+        targets
+    }
+
+    fn marked_size(&self) -> u64 {
+        self.deletion_targets().iter().map(|index| self.tree.nodes[*index].size).sum()
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Outcome> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => return Some(Outcome::Abort),
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Outcome::Quit),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+            KeyCode::PageUp => self.move_cursor(-(self.page as isize)),
+            KeyCode::PageDown => self.move_cursor(self.page as isize),
+            KeyCode::Home | KeyCode::Char('g') => self.cursor = 0,
+            KeyCode::End | KeyCode::Char('G') => self.move_cursor(isize::MAX),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => self.enter(),
+            KeyCode::Left | KeyCode::Backspace | KeyCode::Char('h') => self.leave(),
+            KeyCode::Char(' ' | 'd') => self.toggle_mark(),
+            KeyCode::Char('s') => {
+                let current = self.current();
+                self.sort = match self.sort {
+                    SortBy::Size => SortBy::Name,
+                    SortBy::Name => SortBy::Size,
+                };
+                // 排序切换后光标仍指向同一条目
+                self.cursor = current
+                    .and_then(|current| self.entries().iter().position(|index| *index == current))
+                    .unwrap_or(0);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [header, main, status] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+
+        let dir = &self.tree.nodes[self.dir];
+        let title = Line::from(vec![
+            Span::styled(dir.path.display().to_string(), Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!("  {}  {} files", format_human_size(dir.size), dir.files)),
+        ]);
+        frame.render_widget(Paragraph::new(title), header);
+
+        self.render_entries(frame, main);
+
+        let sort = match self.sort {
+            SortBy::Size => "size",
+            SortBy::Name => "name",
+        };
+        let text = format!(
+            "{} marked ({}) · sort: {} · ←/→ navigate · Space mark · s sort · q quit and delete marked · ^C abort",
+            self.marked.len(),
+            format_human_size(self.marked_size()),
+            sort
+        );
+        frame.render_widget(Paragraph::new(text).style(Style::default().fg(Color::DarkGray)), status);
+    }
+
+    fn render_entries(&mut self, frame: &mut Frame, area: Rect) {
+        self.page = area.height.saturating_sub(2).max(1) as usize;
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.cursor >= self.offset + self.page {
+            self.offset = self.cursor + 1 - self.page;
+        }
+
+        let total = self.tree.nodes[self.dir].size;
+        let items: Vec<ListItem> = self
+            .entries()
+            .into_iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(self.page)
+            .map(|(row, index)| {
+                let node = &self.tree.nodes[index];
+                let ratio = if total == 0 { 0.0 } else { node.size as f64 / total as f64 };
+                let filled = (ratio * BAR_WIDTH as f64).round() as usize;
+                let bar = format!("{}{}", "█".repeat(filled), " ".repeat(BAR_WIDTH - filled));
+                let name = node.name.to_string_lossy();
+                let name = if node.is_dir { format!("{}/", name) } else { name.into_owned() };
+                let marked = self.marked.contains(&index);
+
+                let mut style = Style::default();
+                if marked {
+                    style = style.fg(Color::Red);
+                }
+                if row == self.cursor {
+                    style = style.bg(Color::DarkGray);
+                }
+                ListItem::new(Line::from(vec![
+                    Span::raw(if marked { "* " } else { "  " }),
+                    Span::raw(format!("{:>10} ", format_human_size(node.size))),
+                    Span::styled(format!("[{}]", bar), Style::default().fg(Color::Cyan)),
+                    Span::raw(format!(" {:>5.1}% {:>8}  ", ratio * 100.0, node.files)),
+                    Span::styled(name, if node.is_dir { Style::default().add_modifier(Modifier::BOLD) } else { Style::default() }),
+                ]))
+                .style(style)
+            })
+            .collect();
+
+        frame.render_widget(List::new(items).block(Block::bordered()), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn names(browser: &Browser) -> Vec<String> {
+        browser
+            .entries()
+            .iter()
+            .map(|index| browser.tree.nodes[*index].name.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn browser(root: &Path) -> Browser {
+        fs::create_dir_all(root.join("big/inner")).unwrap();
+        fs::write(root.join("big/inner/blob"), vec![0; 1000]).unwrap();
+        fs::write(root.join("big/note"), vec![0; 10]).unwrap();
+        fs::write(root.join("small"), vec![0; 100]).unwrap();
+        let matches: Vec<FoundFile> = ["big/inner/blob", "big/note", "small"]
+            .iter()
+            .map(|p| FoundFile { path: root.join(p), depth: 0, member: None })
+            .collect();
+        Browser::new(DuTree::build(&matches, root))
+    }
+
+    #[test]
+    fn test_navigation_and_sorting() {
+        let dir = TempDir::new().unwrap();
+        let mut browser = browser(dir.path());
+        assert_eq!(names(&browser), ["big", "small"]);
+
+        browser.handle_key(key(KeyCode::Enter));
+        assert_eq!(names(&browser), ["inner", "note"]);
+        browser.handle_key(key(KeyCode::Down));
+        browser.handle_key(key(KeyCode::Char('s')));
+        assert_eq!(names(&browser), ["inner", "note"]);
+        assert_eq!(browser.cursor, 1);
+
+        browser.handle_key(key(KeyCode::Left));
+        assert_eq!(browser.dir, 0);
+        assert_eq!(browser.tree.nodes[browser.current().unwrap()].name, "big");
+    }
+
+    #[test]
+    fn test_marks_covered_by_parent_are_not_deleted_twice() {
+        let dir = TempDir::new().unwrap();
+        let mut browser = browser(dir.path());
+
+        browser.handle_key(key(KeyCode::Enter));
+        browser.handle_key(key(KeyCode::Char(' ')));
+        browser.handle_key(key(KeyCode::Left));
+        browser.handle_key(key(KeyCode::Char(' ')));
+        browser.handle_key(key(KeyCode::Char(' ')));
+
+        let targets: Vec<_> = browser
+            .deletion_targets()
+            .iter()
+            .map(|index| browser.tree.nodes[*index].name.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(targets, ["big", "small"]);
+        assert_eq!(browser.marked_size(), 1110);
+        assert_eq!(browser.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)), Some(Outcome::Abort));
+    }
+
+    #[test]
+    fn test_only_directories_emptied_by_deletion_are_removed() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("big/inner")).unwrap();
+        fs::create_dir_all(root.join("big/empty")).unwrap();
+        fs::write(root.join("big/inner/blob"), vec![0; 1000]).unwrap();
+        fs::write(root.join("big/note"), vec![0; 10]).unwrap();
+        let matches: Vec<FoundFile> = ["big/inner/blob", "big/note", "big/empty"]
+            .iter()
+            .map(|p| FoundFile { path: root.join(p), depth: 0, member: None })
+            .collect();
+        let tree = DuTree::build(&matches, root);
+        let big = tree.nodes.iter().position(|node| node.name == "big").unwrap();
+
+        assert_eq!(remove_matched_under(&tree, big), 1010);
+        assert!(!root.join("big/inner").exists());
+        // 原本就为空的目录不是这次删空的，连同其上级一起保留
+        assert!(root.join("big/empty").is_dir());
+    }
+}
@@ -0,0 +1,116 @@
+/// 模糊匹配：查询中的字符按顺序出现在候选中即匹配，返回分数和匹配到的字符位置
+///
+/// 查询不含大写字母时忽略大小写。优先在最后一级路径（文件名）中匹配，
+/// 连续匹配和单词开头的匹配得分更高。
+pub fn fuzzy_match(candidate: &str, query: &str) -> Option<(i64, Vec<usize>)> {
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    let ignore_case = !query.chars().any(char::is_uppercase);
+    let chars: Vec<char> = candidate.chars().collect();
+    let query: Vec<char> = query.chars().collect();
+
+    let name_start = chars.iter().rposition(|c| *c == '/').map_or(0, |pos| pos + 1);
+    let positions = match_from(&chars, &query, name_start, ignore_case)
+        .or_else(|| match_from(&chars, &query, 0, ignore_case))?;
+
+    Some((score(&chars, &positions, name_start), positions))
+}
+
+fn char_eq(a: char, b: char, ignore_case: bool) -> bool {
+    if ignore_case {
+        a.to_lowercase().eq(b.to_lowercase())
+    } else {
+        a == b
+    }
+}
+
+/// 先从 `start` 向后贪心匹配找到结束位置，再从结束位置向前收紧，得到尽量紧凑的匹配
+fn match_from(chars: &[char], query: &[char], start: usize, ignore_case: bool) -> Option<Vec<usize>> {
+    let mut next = 0;
+    let mut end = None;
+    for (pos, c) in chars.iter().enumerate().skip(start) {
+        if char_eq(*c, query[next], ignore_case) {
+            next += 1;
+            if next == query.len() {
+                end = Some(pos);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    let mut positions = Vec::with_capacity(query.len());
+    let mut remaining = query.len();
+    for pos in (start..=end).rev() {
+        if remaining > 0 && char_eq(chars[pos], query[remaining - 1], ignore_case) {
+            positions.push(pos);
+            remaining -= 1;
+        }
+    }
+    positions.reverse();
+    Some(positions)
+}
+
+fn score(chars: &[char], positions: &[usize], name_start: usize) -> i64 {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+
+    for &pos in positions {
+        score += 16;
+
+        // 单词开头：路径或分隔符之后，或者驼峰的大写字母
+        let at_boundary = match pos.checked_sub(1).map(|p| chars[p]) {
+            None => true,
+            Some(before) => {
+                matches!(before, '/' | '_' | '-' | '.' | ' ')
+                    || (before.is_lowercase() && chars[pos].is_uppercase())
+            }
+        };
+        if at_boundary {
+            score += 8;
+        }
+
+        match previous {
+            Some(previous) if pos == previous + 1 => score += 12,
+            Some(previous) => score -= (pos - previous - 1).min(20) as i64,
+            None => {}
+        }
+        previous = Some(pos);
+    }
+
+    if positions.first().is_some_and(|first| *first >= name_start) {
+        score += 20;
+    }
+    // 同等条件下更短的路径更靠前
+    score - (chars.len() as i64 / 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(candidate: &str, query: &str) -> Option<Vec<usize>> {
+        fuzzy_match(candidate, query).map(|(_, positions)| positions)
+    }
+
+    #[test]
+    fn test_subsequence_matching() {
+        assert_eq!(positions("src/main.rs", "mrs"), Some(vec![4, 9, 10]));
+        assert_eq!(positions("src/main.rs", "xyz"), None);
+        assert_eq!(positions("Cargo.toml", "ct"), Some(vec![0, 6]));
+        assert_eq!(positions("Cargo.toml", "cT"), None);
+        assert_eq!(positions("anything", ""), Some(vec![]));
+    }
+
+    #[test]
+    fn test_prefers_file_name_and_tight_matches() {
+        let score = |candidate| fuzzy_match(candidate, "conf").unwrap().0;
+        assert!(score("etc/app.conf") > score("config/other/app.txt.nfo"));
+        assert!(score("src/config.rs") > score("src/c_o_n_f.rs"));
+
+        // 文件名中匹配不到时退回到整个路径
+        assert_eq!(positions("docs/readme.md", "dr"), Some(vec![0, 5]));
+    }
+}
@@ -0,0 +1,50 @@
+use std::io::{self, IsTerminal, Stderr};
+
+use ratatui::{
+    backend::CrosstermBackend,
+    crossterm::{
+        execute,
+        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    },
+    Terminal,
+};
+
+use crate::types::{FindError, FindResult};
+
+pub mod du;
+pub mod fuzzy;
+pub mod picker;
+
+pub type Term = Terminal<CrosstermBackend<Stderr>>;
+
+/// 全屏终端会话，界面画在 stderr 上，stdout 保留给选择结果以便继续管道处理
+///
+/// drop 时恢复终端，即使中途出错也不会留下 raw 模式。
+pub struct TerminalSession {
+    pub terminal: Term,
+}
+
+impl TerminalSession {
+    pub fn start() -> FindResult<Self> {
+        if !io::stderr().is_terminal() {
+            return Err(FindError::Terminal("interactive mode requires a terminal".to_string()));
+        }
+
+        enable_raw_mode()?;
+        if let Err(e) = execute!(io::stderr(), EnterAlternateScreen) {
+            let _ = disable_raw_mode();
+            return Err(e.into());
+        }
+
+        let terminal = Terminal::new(CrosstermBackend::new(io::stderr()))?;
+        Ok(TerminalSession { terminal })
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stderr(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
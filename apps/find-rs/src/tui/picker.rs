@@ -0,0 +1,551 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::Read,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::{
+    actions::{self, delete, exec, format, paths::PathStyle},
+    matcher::finder::search_each,
+    tui::{fuzzy::fuzzy_match, Term, TerminalSession},
+    types::{Cli, FindResult, FoundFile},
+};
+
+/// 预览文件开头时最多读取的字节数
+const PREVIEW_BYTES: u64 = 16 * 1024;
+
+/// 后台搜索线程发给界面的消息
+enum Message {
+    Found(FoundFile),
+    Done(Result<(), String>),
+}
+
+/// 用户在界面中选择的操作
+#[derive(Debug)]
+enum Outcome {
+    Cancel,
+    /// 执行命令行上配置的动作（默认打印）
+    Run(Vec<FoundFile>),
+    Delete(Vec<FoundFile>),
+    Exec(Vec<FoundFile>),
+}
+
+#[derive(Debug, PartialEq)]
+enum SearchState {
+    Running,
+    Done,
+    Failed(String),
+}
+
+struct Item {
+    file: FoundFile,
+    label: String,
+}
+
+struct Matched {
+    index: usize,
+    score: i64,
+    positions: Vec<usize>,
+}
+
+/// 交互选择器的状态，与终端绘制分离以便测试
+struct Picker {
+    items: Vec<Item>,
+    query: String,
+    matched: Vec<Matched>,
+    /// 新条目加入后需要按分数重新排序
+    unsorted: bool,
+    cursor: usize,
+    offset: usize,
+    /// 列表区域的可见行数，绘制时更新
+    page: usize,
+    selected: BTreeSet<usize>,
+    search: SearchState,
+    has_exec: bool,
+    message: Option<String>,
+    preview: Option<(PathBuf, Vec<String>)>,
+}
+
+/// `--interactive`：边搜索边展示匹配项，选择后执行动作
+pub fn run(cli: &Cli) -> FindResult<()> {
+    let (sender, receiver) = mpsc::channel();
+    spawn_search(cli.clone(), sender);
+
+    let mut picker = Picker::new(cli.actions.exec.is_some());
+    let outcome = {
+        let mut session = TerminalSession::start()?;
+        event_loop(&mut session.terminal, &mut picker, &receiver)?
+    };
+
+    match outcome {
+        Outcome::Cancel => Ok(()),
+        Outcome::Run(files) => actions::do_action(&files, cli),
+        Outcome::Delete(files) => {
+            for file in files.iter().filter(|file| file.member.is_none()) {
+                delete::delete_file(&file.path, cli.actions.force)?;
+            }
+            Ok(())
+        }
+        Outcome::Exec(files) => {
+            if let Some(cmd) = &cli.actions.exec {
+                let style = PathStyle::from_cli(cli)?;
+                for file in &files {
+                    exec::execute_command(cmd, &style.display(file))?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 在后台线程中搜索；界面退出后接收端被丢弃，发送失败时搜索随之停止
+fn spawn_search(cli: Cli, sender: Sender<Message>) {
+    thread::spawn(move || {
+        let result = search_each(&cli, |file| sender.send(Message::Found(file)).is_ok());
+        let _ = sender.send(Message::Done(result.map_err(|e| e.to_string())));
+    });
+}
+
+fn event_loop(terminal: &mut Term, picker: &mut Picker, receiver: &Receiver<Message>) -> FindResult<Outcome> {
+    loop {
+        // 每帧最多接收一批，避免大量结果时界面失去响应
+        for message in receiver.try_iter().take(10_000) {
+            match message {
+                Message::Found(file) => picker.push(file),
+                Message::Done(Ok(())) => picker.search = SearchState::Done,
+                Message::Done(Err(e)) => picker.search = SearchState::Failed(e),
+            }
+        }
+
+        terminal.draw(|frame| picker.render(frame))?;
+
+        if event::poll(Duration::from_millis(50))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    if let Some(outcome) = picker.handle_key(key) {
+                        return Ok(outcome);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Picker {
+    fn new(has_exec: bool) -> Self {
+        Picker {
+            items: Vec::new(),
+            query: String::new(),
+            matched: Vec::new(),
+            unsorted: false,
+            cursor: 0,
+            offset: 0,
+            page: 10,
+            selected: BTreeSet::new(),
+            search: SearchState::Running,
+            has_exec,
+            message: None,
+            preview: None,
+        }
+    }
+
+    fn push(&mut self, file: FoundFile) {
+        let label = file.path.display().to_string();
+        let index = self.items.len();
+        if let Some((score, positions)) = fuzzy_match(&label, &self.query) {
+            self.matched.push(Matched { index, score, positions });
+            self.unsorted = !self.query.is_empty();
+        }
+        self.items.push(Item { file, label });
+    }
+
+    fn set_query(&mut self, query: String) {
+        self.query = query;
+        self.matched = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| {
+                fuzzy_match(&item.label, &self.query).map(|(score, positions)| Matched { index, score, positions })
+            })
+            .collect();
+        self.unsorted = true;
+        self.cursor = 0;
+        self.offset = 0;
+    }
+
+    fn sort(&mut self) {
+        if self.unsorted {
+            // 查询为空时保持搜索顺序，否则分数高的在前
+            self.matched.sort_by(|a, b| b.score.cmp(&a.score).then(a.index.cmp(&b.index)));
+            self.unsorted = false;
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        if self.matched.is_empty() {
+            self.cursor = 0;
+            return;
+        }
+        let last = self.matched.len() - 1;
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    fn current(&self) -> Option<usize> {
+        self.matched.get(self.cursor).map(|m| m.index)
+    }
+
+    fn toggle_current(&mut self) {
+        if let Some(index) = self.current() {
+            if !self.selected.remove(&index) {
+                self.selected.insert(index);
+            }
+        }
+    }
+
+    /// 全部已选中时取消选择，否则选中所有过滤后的条目
+    fn toggle_all(&mut self) {
+        let all_selected = self.matched.iter().all(|m| self.selected.contains(&m.index));
+        for m in &self.matched {
+            if all_selected {
+                self.selected.remove(&m.index);
+            } else {
+                self.selected.insert(m.index);
+            }
+        }
+    }
+
+    /// 已选中的条目按搜索顺序返回；没有选中时使用光标所在的条目
+    fn selection(&self) -> Vec<FoundFile> {
+        if self.selected.is_empty() {
+            return self.current().map(|i| vec![self.items[i].file.clone()]).unwrap_or_default();
+        }
+        self.selected.iter().map(|i| self.items[*i].file.clone()).collect()
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Outcome> {
+        self.sort();
+        self.message = None;
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Esc => return Some(Outcome::Cancel),
+            KeyCode::Char('c' | 'g') if ctrl => return Some(Outcome::Cancel),
+            KeyCode::Enter => {
+                let selection = self.selection();
+                if !selection.is_empty() {
+                    return Some(Outcome::Run(selection));
+                }
+            }
+            KeyCode::Char('d') if ctrl => {
+                let selection = self.selection();
+                if !selection.is_empty() {
+                    return Some(Outcome::Delete(selection));
+                }
+            }
+            KeyCode::Char('x') if ctrl => {
+                if !self.has_exec {
+                    self.message = Some("no --exec command configured".to_string());
+                } else {
+                    let selection = self.selection();
+                    if !selection.is_empty() {
+                        return Some(Outcome::Exec(selection));
+                    }
+                }
+            }
+            KeyCode::Tab => {
+                self.toggle_current();
+                self.move_cursor(1);
+            }
+            KeyCode::BackTab => {
+                self.toggle_current();
+                self.move_cursor(-1);
+            }
+            KeyCode::Char('a') if ctrl => self.toggle_all(),
+            KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Char('p' | 'k') if ctrl => self.move_cursor(-1),
+            KeyCode::Down => self.move_cursor(1),
+            KeyCode::Char('n' | 'j') if ctrl => self.move_cursor(1),
+            KeyCode::PageUp => self.move_cursor(-(self.page as isize)),
+            KeyCode::PageDown => self.move_cursor(self.page as isize),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.move_cursor(isize::MAX),
+            KeyCode::Backspace => {
+                let mut query = self.query.clone();
+                query.pop();
+                self.set_query(query);
+            }
+            KeyCode::Char('u') if ctrl => self.set_query(String::new()),
+            KeyCode::Char('w') if ctrl => {
+                // 先去掉结尾的空格和分隔符，`a/` 删除整个 `a/`
+                let query = self.query.trim_end_matches([' ', '/']);
+                let keep = query.rfind([' ', '/']).map_or(0, |pos| pos + 1);
+                self.set_query(query[..keep].to_string());
+            }
+            KeyCode::Char(c) if !ctrl => {
+                let mut query = self.query.clone();
+                query.push(c);
+                self.set_query(query);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.sort();
+        let [main, prompt, status] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(1), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(main);
+
+        self.render_list(frame, list_area);
+        self.render_preview(frame, preview_area);
+
+        let prompt_line = Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(self.query.as_str()),
+        ]);
+        frame.render_widget(Paragraph::new(prompt_line), prompt);
+        frame.set_cursor_position(Position::new(
+            prompt.x + 2 + self.query.chars().count() as u16,
+            prompt.y,
+        ));
+
+        let state = match &self.search {
+            SearchState::Running => "searching…".to_string(),
+            SearchState::Done => "done".to_string(),
+            SearchState::Failed(e) => format!("error: {}", e),
+        };
+        let text = match &self.message {
+            Some(message) => message.clone(),
+            None => format!(
+                "{}/{} ({} selected) {} · Tab select · Enter run · ^D delete · ^X exec · Esc quit",
+                self.matched.len(),
+                self.items.len(),
+                self.selected.len(),
+                state
+            ),
+        };
+        frame.render_widget(Paragraph::new(text).style(Style::default().fg(Color::DarkGray)), status);
+    }
+
+    fn render_list(&mut self, frame: &mut Frame, area: Rect) {
+        self.page = area.height.saturating_sub(2).max(1) as usize;
+        // 保持光标在可见范围内
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.cursor >= self.offset + self.page {
+            self.offset = self.cursor + 1 - self.page;
+        }
+
+        let highlight = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        let items: Vec<ListItem> = self
+            .matched
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(self.page)
+            .map(|(row, m)| {
+                let item = &self.items[m.index];
+                let marker = if self.selected.contains(&m.index) { "● " } else { "  " };
+                let mut spans = vec![Span::styled(marker, Style::default().fg(Color::Green))];
+                spans.extend(item.label.chars().enumerate().map(|(pos, c)| {
+                    if m.positions.binary_search(&pos).is_ok() {
+                        Span::styled(c.to_string(), highlight)
+                    } else {
+                        Span::raw(c.to_string())
+                    }
+                }));
+
+                let style = if row == self.cursor {
+                    Style::default().bg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(spans)).style(style)
+            })
+            .collect();
+
+        frame.render_widget(List::new(items).block(Block::bordered().title(" Matches ")), area);
+    }
+
+    fn render_preview(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(index) = self.current() else {
+            frame.render_widget(Block::bordered().title(" Preview "), area);
+            return;
+        };
+
+        let file = &self.items[index].file;
+        if self.preview.as_ref().is_none_or(|(path, _)| *path != file.path) {
+            self.preview = Some((file.path.clone(), preview_lines(file)));
+        }
+        let lines: Vec<Line> = self
+            .preview
+            .as_ref()
+            .map(|(_, lines)| lines.iter().map(|line| Line::raw(line.as_str())).collect())
+            .unwrap_or_default();
+
+        let title = format!(" {} ", self.items[index].label);
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), area);
+    }
+}
+
+/// 预览内容：目录列出子项，普通文本文件显示开头，其他情况显示简要信息
+fn preview_lines(file: &FoundFile) -> Vec<String> {
+    if let Some(member) = &file.member {
+        return vec![
+            format!("archive member of {}", member.archive.display()),
+            format!("size: {} bytes", member.size),
+        ];
+    }
+
+    let metadata = match fs::symlink_metadata(&file.path) {
+        Ok(metadata) => metadata,
+        Err(e) => return vec![e.to_string()],
+    };
+
+    if metadata.file_type().is_symlink() {
+        return match fs::read_link(&file.path) {
+            Ok(target) => vec![format!("-> {}", target.display())],
+            Err(e) => vec![e.to_string()],
+        };
+    }
+
+    if metadata.is_dir() {
+        let mut names: Vec<String> = match fs::read_dir(&file.path) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    if entry.file_type().is_ok_and(|t| t.is_dir()) { name + "/" } else { name }
+                })
+                .collect(),
+            Err(e) => return vec![e.to_string()],
+        };
+        if names.is_empty() {
+            return vec!["(empty directory)".to_string()];
+        }
+        names.sort();
+        return names;
+    }
+
+    // FIFO 和设备文件打开或读取时可能一直阻塞，只显示类型
+    if !metadata.is_file() {
+        return vec![format!("({})", format::type_name(format::type_char(&metadata)))];
+    }
+
+    let mut head = Vec::new();
+    match fs::File::open(&file.path).and_then(|f| f.take(PREVIEW_BYTES).read_to_end(&mut head)) {
+        Ok(_) if head.contains(&0) => vec![format!("(binary file, {} bytes)", metadata.len())],
+        Ok(_) => String::from_utf8_lossy(&head)
+            .lines()
+            .map(|line| line.replace('\t', "    "))
+            .collect(),
+        Err(e) => vec![e.to_string()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picker(paths: &[&str]) -> Picker {
+        let mut picker = Picker::new(false);
+        for path in paths {
+            picker.push(FoundFile {
+                path: PathBuf::from(path),
+                depth: 1,
+                member: None,
+            });
+        }
+        picker
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn paths(files: Vec<FoundFile>) -> Vec<String> {
+        files.into_iter().map(|f| f.path.display().to_string()).collect()
+    }
+
+    #[test]
+    fn test_filtering_and_selection() {
+        let mut picker = picker(&["src/main.rs", "src/lib.rs", "README.md"]);
+        for c in "rs".chars() {
+            picker.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(picker.matched.len(), 2);
+
+        picker.handle_key(key(KeyCode::Tab));
+        picker.handle_key(key(KeyCode::Tab));
+        match picker.handle_key(key(KeyCode::Enter)) {
+            Some(Outcome::Run(files)) => assert_eq!(paths(files), ["src/main.rs", "src/lib.rs"]),
+            other => panic!("unexpected outcome {:?}", other),
+        }
+
+        // 清空查询后新到达的条目同样可见
+        picker.handle_key(ctrl('u'));
+        picker.push(FoundFile { path: PathBuf::from("late.txt"), depth: 1, member: None });
+        assert_eq!(picker.matched.len(), 4);
+    }
+
+    #[test]
+    fn test_actions_without_selection_use_cursor() {
+        let mut picker = picker(&["a.txt", "b.txt"]);
+        picker.handle_key(key(KeyCode::Down));
+        match picker.handle_key(ctrl('d')) {
+            Some(Outcome::Delete(files)) => assert_eq!(paths(files), ["b.txt"]),
+            other => panic!("unexpected outcome {:?}", other),
+        }
+
+        // 未配置 --exec 时只给出提示
+        assert!(picker.handle_key(ctrl('x')).is_none());
+        assert!(picker.message.is_some());
+        assert!(matches!(picker.handle_key(key(KeyCode::Esc)), Some(Outcome::Cancel)));
+    }
+
+    #[test]
+    fn test_toggle_all() {
+        let mut picker = picker(&["a", "b", "c"]);
+        picker.handle_key(ctrl('a'));
+        assert_eq!(picker.selected.len(), 3);
+        picker.handle_key(ctrl('a'));
+        assert!(picker.selected.is_empty());
+    }
+
+    #[test]
+    fn test_ctrl_w_removes_trailing_separator() {
+        let mut picker = picker(&[]);
+        for (query, expected) in [("src/main", "src/"), ("a/", ""), ("foo bar/", "foo ")] {
+            picker.set_query(query.to_string());
+            picker.handle_key(ctrl('w'));
+            assert_eq!(picker.query, expected);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_preview_skips_special_files() {
+        let device = FoundFile { path: PathBuf::from("/dev/null"), depth: 1, member: None };
+        assert_eq!(preview_lines(&device), ["(char device)"]);
+    }
+}
@@ -1,10 +1,15 @@
-use std::{path::PathBuf, time::Duration};
+use std::{path::PathBuf, time::{Duration, SystemTime}};
 
-use clap::{Parser, ValueEnum, value_parser};
+use clap::{Parser, Subcommand, ValueEnum};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use thiserror::Error;
 
+use crate::{
+    actions::{attributes::Owner, format::PrintFormat, mode::ModeSpec, template::NameTemplate},
+    matcher::perm::PermSpec,
+};
+
 #[derive(Error, Debug)]
 pub enum FindError {
     #[error("I/O error: {0}")]
@@ -30,14 +35,47 @@ pub enum FindError {
 
     #[error("Invalid format string: {0}")]
     FormatError(String),
+
+    #[error("Invalid attribute: {0}")]
+    Attribute(String),
+
+    #[error("Archive error: {0}")]
+    Archive(String),
+
+    #[error("Git error: {0}")]
+    Git(String),
+
+    #[error("Checksum error: {0}")]
+    Checksum(String),
+
+    #[error("Relocation failed: {0}")]
+    Relocate(String),
+
+    #[error("Index error: {0}")]
+    Index(String),
+
+    #[error("Snapshot error: {0}")]
+    Snapshot(String),
+
+    #[error("Terminal error: {0}")]
+    Terminal(String),
+
+    #[error("Config error: {0}")]
+    Config(String),
 }
 
 pub type FindResult<T> = std::result::Result<T, FindError>;
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Clone, Debug)]
 #[command(author, version, about, long_about = None)]
 // #[command(next_line_help(true))]
+#[command(args_conflicts_with_subcommands = true)]
+// 命名搜索展开后，命令行上后出现的同名选项覆盖前面的值
+#[command(args_override_self = true)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// The directory to search in 
     #[arg(default_value = ".", value_parser = validate_path)]
     pub path: PathBuf,
@@ -49,6 +87,14 @@ pub struct Cli {
     /// Actions to perform on matching files
     #[command(flatten)]
     pub actions: FileActions,
+
+    /// Ignore ~/.config/find-rs/config.toml and .find-rs.toml
+    #[arg(long)]
+    pub no_config: bool,
+
+    /// Explain on stderr why each visited entry matched or was rejected; with =PATH only that entry
+    #[arg(long, value_name = "PATH", num_args = 0..=1, require_equals = true)]
+    pub explain: Option<Option<PathBuf>>,
 }
 
 #[derive(clap::Args, Clone, Debug)]
@@ -67,10 +113,18 @@ pub struct SearchCriteria {
     #[arg(short, long, group = "matcher")]
     pub regex: Option<String>,
 
-    /// Case insensitive search
-    #[arg(short, long, requires = "matcher")]
+    /// Case insensitive search (full Unicode case folding)
+    #[arg(short, long, conflicts_with = "case_sensitive")]
     pub insensitive: bool,
 
+    /// Case sensitive search (by default smart case is used: insensitive unless the pattern has uppercase)
+    #[arg(long)]
+    pub case_sensitive: bool,
+
+    /// Normalize Unicode filenames and patterns before matching (e.g. for names created on macOS)
+    #[arg(long, value_enum, value_name = "FORM")]
+    pub normalize: Option<Normalization>,
+
     /// Filter by file size (e.g., +1M, -500K, 100)
     #[arg(short, long, value_parser = parse_size_spec)]
     pub size: Option<SizeSpec>,
@@ -79,28 +133,231 @@ pub struct SearchCriteria {
     #[arg(short, long, value_parser = parse_time_spec)]
     pub mtime: Option<TimeSpec>,
 
+    /// Match empty regular files and empty directories
+    #[arg(long)]
+    pub empty: bool,
+
+    /// Filter by MIME type sniffed from file contents (e.g., 'image/*', application/pdf)
+    #[arg(long, value_name = "PATTERN")]
+    pub mime: Option<String>,
+
+    /// Filter by content kind sniffed from the first bytes of the file
+    #[arg(long, value_enum)]
+    pub kind: Option<FileKind>,
+
+    /// Filter by inode number
+    #[arg(long, value_name = "N")]
+    pub inum: Option<u64>,
+
+    /// Filter by hard link count (e.g., 1, +1, -3)
+    #[arg(long, value_name = "N", value_parser = parse_link_spec, allow_hyphen_values = true)]
+    pub links: Option<SizeSpec>,
+
+    /// Find files that are the same file (same inode and device) as the given path
+    #[arg(long, value_name = "PATH", value_parser = parse_samefile)]
+    pub samefile: Option<FileId>,
+
+    /// Filter by permission bits: MODE for an exact match, -MODE for all bits set,
+    /// /MODE for any bit set (octal or symbolic, e.g. 644, -u+x, /o+w)
+    #[arg(long, value_name = "MODE", value_parser = parse_perm_spec, allow_hyphen_values = true)]
+    pub perm: Option<PermSpec>,
+
     /// Filter by file type
     #[arg(short = 't', long, value_enum)]
     pub filter_type: Option<FilterType>,
     
     /// Search hidden files and directories
-    #[arg(short, long)]
+    #[arg(short, long, overrides_with = "no_all")]
     pub all: bool,
+
+    /// Skip hidden files and directories even if the config enables 'hidden'
+    #[arg(long, overrides_with = "all")]
+    pub no_all: bool,
+
+    /// Additional name patterns treated as hidden, besides dotfiles (e.g. '*~', '__pycache__')
+    #[arg(long = "hidden-pattern", value_name = "PATTERN", conflicts_with = "all")]
+    pub hidden_patterns: Vec<String>,
     
-    /// Maximum search depth (0 = only current directory)
-    #[arg(short, long, value_parser = value_parser!(u8).range(0..=255))]
-    pub depth: Option<u8>,
+    /// Skip entries matching this glob (on name or path relative to the search root); excluded directories are never opened
+    #[arg(short = 'E', long = "exclude", value_name = "GLOB")]
+    pub excludes: Vec<String>,
+
+    /// Do not add the exclude patterns from the config file
+    #[arg(long)]
+    pub no_config_excludes: bool,
+
+    /// Do not descend into directories that match the search criteria
+    #[arg(long, conflicts_with = "post_order")]
+    pub prune: bool,
+
+    /// Visit directory contents before the directory itself (depth-first, post-order)
+    #[arg(long, visible_alias = "contents-first")]
+    pub post_order: bool,
+
+    /// Maximum search depth (0 = only the start path itself)
+    #[arg(short, long, visible_alias = "max-depth", value_name = "DEPTH", overrides_with = "no_max_depth")]
+    pub depth: Option<usize>,
+
+    /// Search without a depth limit even if the config sets 'max_depth'
+    #[arg(long, overrides_with = "depth")]
+    pub no_max_depth: bool,
+
+    /// Minimum search depth (1 = skip the start path, list only its descendants)
+    #[arg(long, value_name = "DEPTH")]
+    pub min_depth: Option<usize>,
+
+    /// Stay on the filesystem of the start path (do not descend into other mounts)
+    #[arg(long, visible_alias = "xdev", overrides_with = "no_one_file_system")]
+    pub one_file_system: bool,
+
+    /// Cross filesystem boundaries even if the config enables 'one_file_system'
+    #[arg(long, overrides_with = "one_file_system")]
+    pub no_one_file_system: bool,
+
+    /// Filter by filesystem type (e.g. ext4, tmpfs, proc), resolved from /proc/self/mountinfo
+    #[arg(long, value_name = "TYPE")]
+    pub fstype: Option<String>,
+
+    /// Only files with staged or unstaged changes in the git working copy
+    #[arg(long)]
+    pub git_modified: bool,
+
+    /// Only files not tracked by git
+    #[arg(long)]
+    pub git_untracked: bool,
+
+    /// Only files ignored by git
+    #[arg(long)]
+    pub git_ignored: bool,
+
+    /// Only tracked files changed between the given revision and the working tree
+    #[arg(long, value_name = "REV")]
+    pub git_changed_since: Option<String>,
+
+    /// Descend into tar/tar.gz/zip archives and report members as 'archive.tar.gz!/path'
+    #[arg(long)]
+    pub search_archives: bool,
 
     /// Follow symbolic links
-    #[arg(short = 'L', long)]
+    #[arg(short = 'L', long, overrides_with = "no_follow")]
     pub follow_symlinks: bool,
+
+    /// Do not follow symbolic links even if the config enables 'follow_symlinks'
+    #[arg(long, overrides_with = "follow_symlinks")]
+    pub no_follow: bool,
+
+    /// Answer the query from a persistent index built with 'find-rs index build'
+    /// instead of walking the tree (name, regex, type, size, mtime, perm and depth filters)
+    #[arg(long)]
+    pub from_index: bool,
+
+    /// Index file to use with --from-index (default: the index of the nearest indexed parent directory)
+    #[arg(long, value_name = "FILE", requires = "from_index")]
+    pub index_file: Option<PathBuf>,
 }
 
-#[derive(clap::Args, Clone, Debug)]
+#[derive(Subcommand, Clone, Debug)]
+pub enum Command {
+    /// Manage the persistent index used by --from-index
+    Index {
+        #[command(subcommand)]
+        command: IndexCommand,
+    },
+
+    /// Record the matches of a search and compare them between runs
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommand,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum SnapshotCommand {
+    /// Save path, size, mtime, mode and optional hash of every match to FILE
+    Save {
+        /// Snapshot file to write
+        file: PathBuf,
+
+        /// The directory to search in
+        #[arg(default_value = ".", value_parser = validate_path)]
+        path: PathBuf,
+
+        /// Also record a content hash of every regular file (enables rename detection)
+        #[arg(long, value_enum, value_name = "ALGORITHM")]
+        hash: Option<HashAlgorithm>,
+
+        #[command(flatten)]
+        criteria: Box<SearchCriteria>,
+
+        /// 命令行上给出的搜索条件，由 `parse_cli` 填入并记录在快照中
+        #[arg(skip)]
+        args: Vec<String>,
+    },
+
+    /// Report added, removed, modified and renamed files between two snapshots,
+    /// or between a snapshot and the live tree when NEW is omitted
+    Diff {
+        /// Older snapshot
+        old: PathBuf,
+
+        /// Newer snapshot (default: search the snapshot's root again)
+        new: Option<PathBuf>,
+
+        /// Search criteria for the live tree, only used for snapshots that did not record
+        /// the filters they were saved with
+        #[command(flatten)]
+        criteria: Box<SearchCriteria>,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum IndexCommand {
+    /// Build the index for ROOT, re-reading only directories whose mtime changed since the last build
+    Build {
+        /// Directory to index
+        #[arg(default_value = ".", value_parser = validate_path)]
+        root: PathBuf,
+
+        /// Write the index to FILE instead of the per-root file in the cache directory
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Re-read every directory instead of refreshing incrementally
+        #[arg(long)]
+        full: bool,
+
+        /// Do not descend into other mounted filesystems
+        #[arg(long, visible_alias = "xdev")]
+        one_file_system: bool,
+    },
+}
+
+#[derive(clap::Args, Clone, Debug, Default)]
 pub struct FileActions {
-    /// Print matching files (supports format strings: %p=path, %f=filename, %s=size, %t=mod time)
-    #[arg(short, long, default_value= "%p", value_name = "FORMAT")]
-    pub print: Option<String>,
+    /// Print matching files using a printf-style format
+    /// (%p=path, %P=path relative to root, %f=filename, %e=parent dir, %d=depth,
+    /// %s=size, %h=human size, %t=mod time, %T{strftime}=custom time, %m=octal mode,
+    /// %M=ls-style perms, %u/%g=owner/group, %i=inode, %l=link target, %k=1K blocks,
+    /// %y=type, %H{sha256|blake3|md5}=file hash, %%=literal %; width/alignment like %-10f).
+    /// Defaults to %p when no other action is given
+    #[arg(short, long, value_name = "FORMAT", value_parser = parse_print_format)]
+    pub print: Option<PrintFormat>,
+
+    /// Print checksums of matching files in `sha256sum` format (computed in parallel);
+    /// with --verify, the algorithm the manifest was written with
+    #[arg(long, visible_alias = "algorithm", value_enum, value_name = "ALGORITHM")]
+    pub checksum: Option<HashAlgorithm>,
+
+    /// Verify files listed in a checksum manifest instead of searching
+    /// (algorithm taken from --checksum/--algorithm, otherwise md5 for 32 hex digits
+    /// and sha256 or blake3 for 64)
+    #[arg(long, value_name = "MANIFEST")]
+    pub verify: Option<PathBuf>,
+
+    /// Write all matching files into a tar, tar.gz or zip archive, with paths relative
+    /// to the search root and permissions and modification times preserved
+    #[arg(long, value_name = "OUTPUT")]
+    pub archive: Option<PathBuf>,
 
     /// Delete matching files (requires confirmation unless --force is used)
     #[arg(long)]
@@ -113,6 +370,145 @@ pub struct FileActions {
     /// Execute a command on matching files (use {} as placeholder for file path)
     #[arg(short = 'x', long, value_name = "COMMAND")]
     pub exec: Option<String>,
+
+    /// Change permissions of matching files (octal or symbolic, e.g. 644, o-w, u+x,go-w, a+rX)
+    #[arg(long, value_name = "MODE", value_parser = parse_mode_spec)]
+    pub chmod: Option<ModeSpec>,
+
+    /// Change owner and/or group of matching files (user, user:group or :group; names or ids)
+    #[arg(long, value_name = "USER:GROUP", value_parser = parse_owner)]
+    pub chown: Option<Owner>,
+
+    /// Set access and modification times of matching files to now or to --touch=TIMESTAMP
+    /// (@epoch, YYYY-MM-DD, "YYYY-MM-DD HH:MM[:SS]" or RFC 3339)
+    #[arg(long, value_name = "TIMESTAMP", num_args = 0..=1, require_equals = true, value_parser = parse_timestamp)]
+    pub touch: Option<Option<SystemTime>>,
+
+    /// Rename matching files in place using a template
+    /// ({stem}, {ext}, {name}, {mtime[:strftime]}, {N}/{NAME}=--regex capture groups, {{ and }} for braces)
+    #[arg(long, value_name = "TEMPLATE", value_parser = parse_name_template, conflicts_with = "delete")]
+    pub rename: Option<NameTemplate>,
+
+    /// Move matching files into DIR, keeping their path relative to the search root
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["copy_to", "delete"])]
+    pub move_to: Option<PathBuf>,
+
+    /// Copy matching files into DIR, keeping their path relative to the search root
+    #[arg(long, value_name = "DIR", conflicts_with = "delete")]
+    pub copy_to: Option<PathBuf>,
+
+    /// Show what --rename/--move-to/--copy-to would do without touching any file
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Abort if any target conflicts and roll back completed operations on failure
+    #[arg(long)]
+    pub all_or_nothing: bool,
+
+    /// Browse matches in a terminal UI with fuzzy filtering and a preview pane; Enter runs
+    /// the configured action (default: print) on the selection, Ctrl-D deletes, Ctrl-X runs --exec
+    #[arg(long, conflicts_with_all = ["verify", "checksum", "archive"])]
+    pub interactive: bool,
+
+    /// Summarize the apparent size and file count of matching files per directory
+    /// instead of listing them
+    #[arg(long, conflicts_with_all = ["print", "exec", "checksum", "archive", "delete", "interactive"])]
+    pub du: bool,
+
+    /// With --du, browse directories sorted by size in a terminal UI; entries marked with
+    /// Space are deleted (after confirmation) on quit
+    #[arg(long, requires = "du")]
+    pub tui: bool,
+
+    /// Print aggregate statistics (per type, extension and owner, size histogram,
+    /// oldest/newest files, deepest paths) instead of listing matches
+    #[arg(long, conflicts_with_all = ["print", "exec", "checksum", "archive", "delete", "interactive", "du"])]
+    pub stats: bool,
+
+    /// Output format for the default listing (one JSON object per line) and --stats
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, conflicts_with = "print")]
+    pub output: OutputFormat,
+
+    /// Color listed paths and the %p, %P and %f fields of --print by file type and extension
+    /// using LS_COLORS, highlighting the matched part of the name
+    /// (auto: only when stdout is a terminal and NO_COLOR is unset)
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Output canonicalized absolute paths (applies to listings, %p, JSON, --exec and --archive)
+    #[arg(long, conflicts_with_all = ["relative_to", "strip_root"])]
+    pub absolute: bool,
+
+    /// Output paths relative to DIR, using '..' where needed
+    #[arg(long, value_name = "DIR", conflicts_with = "strip_root")]
+    pub relative_to: Option<PathBuf>,
+
+    /// Output paths without the search root prefix (the root itself is shown as '.')
+    #[arg(long)]
+    pub strip_root: bool,
+}
+
+/// 一个匹配到的条目
+#[derive(Clone, Debug)]
+pub struct FoundFile {
+    pub path: PathBuf,
+    /// 相对于搜索起点的深度（起点本身为 0）
+    pub depth: usize,
+    /// 如果条目来自压缩包内部，这里保存成员信息，`path` 为虚拟路径
+    pub member: Option<ArchiveMember>,
+}
+
+/// 压缩包中的一个成员
+#[derive(Clone, Debug)]
+pub struct ArchiveMember {
+    /// 压缩包在文件系统中的路径
+    pub archive: PathBuf,
+    /// 成员在压缩包内的路径
+    pub name: PathBuf,
+    pub size: u64,
+    pub modified: SystemTime,
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32,
+    /// 只会是 File、Directory 或 Symlink
+    pub kind: FilterType,
+    pub link_target: Option<PathBuf>,
+}
+
+impl ArchiveMember {
+    /// 形如 `bundle.tar.gz!/lib/foo.so` 的虚拟路径
+    pub fn virtual_path(&self) -> PathBuf {
+        Self::join_virtual(&self.archive, &self.name)
+    }
+
+    pub fn join_virtual(archive: &std::path::Path, name: &std::path::Path) -> PathBuf {
+        let mut path = archive.as_os_str().to_os_string();
+        path.push("!/");
+        path.push(name);
+        PathBuf::from(path)
+    }
+}
+
+/// 文件的唯一标识（设备号 + inode）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FileId {
+    pub dev: u64,
+    pub ino: u64,
+}
+
+impl FileId {
+    #[cfg(unix)]
+    pub fn from_path(path: &std::path::Path) -> std::io::Result<Self> {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = std::fs::symlink_metadata(path)?;
+        Ok(FileId { dev: metadata.dev(), ino: metadata.ino() })
+    }
+
+    #[cfg(not(unix))]
+    pub fn from_path(_path: &std::path::Path) -> std::io::Result<Self> {
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "inode identity is only available on Unix"))
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -131,6 +527,48 @@ pub enum TimeSpec {
     Equal(Duration),
 }
 
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    Sha256,
+    Blake3,
+    Md5,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq)]
+pub enum FileKind {
+    /// Text files (including scripts)
+    Text,
+    /// Non-text files
+    Binary,
+    /// Native executables (ELF, Mach-O, PE) and shebang scripts
+    Executable,
+    /// Compressed files and archives (gzip, zip, tar, xz, ...)
+    Archive,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq)]
+pub enum Normalization {
+    Nfc,
+    Nfd,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum, PartialEq)]
 pub enum FilterType {
     #[clap(name = "f")]
@@ -164,11 +602,35 @@ fn validate_path(s: &str) -> Result<PathBuf, String> {
     Ok(path)
 }
 
+fn parse_print_format(s: &str) -> Result<PrintFormat, String> {
+    PrintFormat::parse(s).map_err(|e| e.to_string())
+}
+
+fn parse_mode_spec(s: &str) -> Result<ModeSpec, String> {
+    ModeSpec::parse(s).map_err(|e| e.to_string())
+}
+
+fn parse_perm_spec(s: &str) -> Result<PermSpec, String> {
+    PermSpec::parse(s).map_err(|e| e.to_string())
+}
+
+fn parse_owner(s: &str) -> Result<Owner, String> {
+    Owner::parse(s).map_err(|e| e.to_string())
+}
+
+fn parse_timestamp(s: &str) -> Result<SystemTime, String> {
+    crate::actions::attributes::parse_timestamp(s).map_err(|e| e.to_string())
+}
+
+fn parse_name_template(s: &str) -> Result<NameTemplate, String> {
+    NameTemplate::parse(s).map_err(|e| e.to_string())
+}
+
 fn parse_size_spec(s: &str) -> Result<SizeSpec, String> {
     let s = s.trim();
     
     let size_pattern = Lazy::new(|| {
-        Regex::new(r"^([+-=]?)(\d+)([kKmMgG]?)$").unwrap()
+        Regex::new(r"^([+=-]?)(\d+)([kKmMgG]?)$").unwrap()
     });
     
     let caps = size_pattern.captures(s).ok_or_else(|| {
@@ -196,16 +658,39 @@ fn parse_size_spec(s: &str) -> Result<SizeSpec, String> {
     match op {
         "+" => Ok(SizeSpec::GreaterThan(size)),
         "-" => Ok(SizeSpec::LessThan(size)),
-        "=" => Ok(SizeSpec::Equal(size)),
+        "" | "=" => Ok(SizeSpec::Equal(size)),
         _ => Err(format!("Invalid operator: {}", op)),
     }
 }
 
+/// 解析硬链接数，与大小一样支持 +N / -N / N 比较
+fn parse_link_spec(s: &str) -> Result<SizeSpec, String> {
+    let s = s.trim();
+    let (op, num_str) = match s.chars().next() {
+        Some(op @ ('+' | '-' | '=')) => (op, &s[1..]),
+        _ => ('=', s),
+    };
+
+    let num = num_str.parse::<u64>()
+        .map_err(|_| format!("Invalid link count: {}", s))?;
+
+    match op {
+        '+' => Ok(SizeSpec::GreaterThan(num)),
+        '-' => Ok(SizeSpec::LessThan(num)),
+        _ => Ok(SizeSpec::Equal(num)),
+    }
+}
+
+fn parse_samefile(s: &str) -> Result<FileId, String> {
+    FileId::from_path(std::path::Path::new(s))
+        .map_err(|e| format!("Cannot stat '{}': {}", s, e))
+}
+
 fn parse_time_spec(s: &str) -> Result<TimeSpec, String> {
     let s = s.trim();
 
     let time_pattern = Lazy::new(|| {
-        Regex::new(r"^([+-=]?)(\d+)([smhdwMy])$").unwrap()
+        Regex::new(r"^([+=-]?)(\d+)([smhdwMy])$").unwrap()
     });
     
     let caps = time_pattern.captures(s).ok_or_else(|| {
@@ -231,7 +716,7 @@ fn parse_time_spec(s: &str) -> Result<TimeSpec, String> {
     match op {
         "+" => Ok(TimeSpec::NewerThan(duration)),
         "-" => Ok(TimeSpec::OlderThan(duration)),
-        "=" => Ok(TimeSpec::Equal(duration)),
+        "" | "=" => Ok(TimeSpec::Equal(duration)),
         _ => Err(format!("Invalid operator: {}", op)),
     }
 }
@@ -246,6 +731,19 @@ impl SizeSpec {
     }
 }
 
+impl SearchCriteria {
+    /// 根据 -i / --case-sensitive 以及 smart case 规则决定是否忽略大小写
+    pub fn ignore_case(&self, pattern: &str, is_regex: bool) -> bool {
+        if self.insensitive {
+            return true;
+        }
+        if self.case_sensitive {
+            return false;
+        }
+        !crate::matcher::name::has_uppercase(pattern, is_regex)
+    }
+}
+
 impl TimeSpec {
     pub fn matches(&self, age: Duration) -> bool {
         match self {
@@ -253,7 +751,7 @@ impl TimeSpec {
             TimeSpec::OlderThan(limit) => age > *limit,
             TimeSpec::Equal(limit) => {
                 let tolerance = Duration::from_secs(1);
-                age >= *limit - tolerance && age <= *limit + tolerance
+                age >= limit.saturating_sub(tolerance) && age <= limit.saturating_add(tolerance)
             }
         }
     }